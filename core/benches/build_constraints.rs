@@ -0,0 +1,56 @@
+//! Time and memory it takes to build a search and hand it to the solver,
+//! for a full set where every champion shares a trait (the densest graph)
+//!   cargo bench --bench build_constraints
+
+use web_time::Instant;
+
+use tft_core::sat::{ ConnectedSubgraphProblem, SubgraphSolver };
+
+const NUM_CHAMPIONS: usize = 60;
+const TEAM_SIZE: usize = 9;
+const RUNS: u32 = 10;
+
+/**
+ * High water mark of the resident set, where the OS reports it
+ */
+fn peak_rss_mb() -> Option<f64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmHWM:"))?;
+    let kb: f64 = line.split_whitespace().nth(1)?.parse().ok()?;
+
+    Some(kb / 1024.0)
+}
+
+fn main() {
+    let edges = Vec::from_iter(
+        (0..NUM_CHAMPIONS)
+            .flat_map(|a| (a + 1..NUM_CHAMPIONS).map(move |b| (a, b)))
+    );
+    let problem = ConnectedSubgraphProblem::new(
+        0..NUM_CHAMPIONS,
+        edges,
+        TEAM_SIZE..=TEAM_SIZE
+    ).expect("edges are between the champions");
+
+    let mut clauses = 0;
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        let constraints = problem.build(TEAM_SIZE);
+        clauses = constraints.stats().clauses;
+
+        let solver = SubgraphSolver::new(constraints);
+        std::hint::black_box(&solver);
+    }
+    let elapsed = start.elapsed() / RUNS;
+
+    println!(
+        "{} champions sharing one trait, team size {}: {} clauses",
+        NUM_CHAMPIONS,
+        TEAM_SIZE,
+        clauses
+    );
+    println!("  build {}ms (mean of {} runs)", elapsed.as_millis(), RUNS);
+    if let Some(mb) = peak_rss_mb() {
+        println!("  peak RSS {:.1}MB", mb);
+    }
+}
@@ -0,0 +1,60 @@
+//! Time it takes to build a search with 9 heavily overlapping slots
+//! and find its first teams, on the densest graph
+//!   cargo bench --bench overlapping_slots
+
+use web_time::{ Duration, Instant };
+
+use tft_core::sat::ConnectedSubgraphProblem;
+
+const NUM_CHAMPIONS: usize = 60;
+const TEAM_SIZE: usize = 9;
+const SLOT_OPTIONS: usize = 24;
+const NUM_TEAMS: usize = 100;
+const RUNS: u32 = 10;
+
+fn main() {
+    let edges = Vec::from_iter(
+        (0..NUM_CHAMPIONS)
+            .flat_map(|a| (a + 1..NUM_CHAMPIONS).map(move |b| (a, b)))
+    );
+    let mut problem = ConnectedSubgraphProblem::new(
+        0..NUM_CHAMPIONS,
+        edges,
+        TEAM_SIZE..=TEAM_SIZE
+    ).expect("edges are between the champions");
+
+    // Each slot shares all but 4 of its options with the next
+    for slot in 0..TEAM_SIZE {
+        let options = Vec::from_iter(slot * 4..slot * 4 + SLOT_OPTIONS);
+        problem.add_slot(&options).expect("options are champions");
+    }
+
+    let mut clauses = 0;
+    let mut build = Duration::ZERO;
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        let build_start = Instant::now();
+        clauses = problem.build(TEAM_SIZE).stats().clauses;
+        build += build_start.elapsed();
+
+        let teams = problem.solutions().take(NUM_TEAMS).count();
+        assert_eq!(teams, NUM_TEAMS);
+    }
+    let elapsed = start.elapsed() / RUNS;
+
+    println!(
+        "{} champions, {} slots of {} options, team size {}: {} clauses",
+        NUM_CHAMPIONS,
+        TEAM_SIZE,
+        SLOT_OPTIONS,
+        TEAM_SIZE,
+        clauses
+    );
+    println!(
+        "  build {}ms, build and first {} teams {}ms (mean of {} runs)",
+        (build / RUNS).as_millis(),
+        NUM_TEAMS,
+        elapsed.as_millis(),
+        RUNS
+    );
+}
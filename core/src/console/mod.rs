@@ -1,7 +1,15 @@
 pub mod api;
 
+// console.log() only exists in the browser, so native builds (eg the cli) print to stderr,
+// keeping stdout free for output like DIMACS or the trait graph
+#[cfg(target_arch = "wasm32")]
 macro_rules! log {
     ($($t:tt)*) => (crate::console::api::log(&format_args!($($t)*).to_string()))
 }
 
+#[cfg(not(target_arch = "wasm32"))]
+macro_rules! log {
+    ($($t:tt)*) => (eprintln!($($t)*))
+}
+
 pub(crate) use log;
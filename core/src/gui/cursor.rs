@@ -0,0 +1,50 @@
+use serde::{ Deserialize, Serialize };
+use wasm_bindgen::prelude::wasm_bindgen;
+
+/**
+ * Snapshot of a search that can be saved (eg to local storage or the url)
+ * and passed back into TeamFinder.reset() to continue where it left off
+ */
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SearchCursor {
+    // Fingerprint of the options the teams were found with
+    pub options_hash: String,
+    // Teams returned so far, in the order they were found
+    pub teams: Vec<Vec<String>>,
+}
+
+/**
+ * 64-bit FNV-1a
+ *
+ * Unlike std's DefaultHasher, the output is guaranteed to be stable
+ * across builds so cursors can outlive a deploy
+ */
+pub struct Fnv64(u64);
+
+impl Fnv64 {
+    pub fn new() -> Self {
+        Self(0xcbf29ce484222325)
+    }
+
+    pub fn write(&mut self, bytes: &[u8]) {
+        for b in bytes {
+            self.0 ^= *b as u64;
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+
+    pub fn finish(&self) -> String {
+        format!("{:016x}", self.0)
+    }
+}
+
+#[wasm_bindgen(typescript_custom_section)]
+const TYPES: &'static str =
+    r#"
+
+export interface ISearchCursor {
+    options_hash: string
+    teams: Array<string[]>
+}
+
+"#;
@@ -0,0 +1,166 @@
+use std::fmt;
+
+use js_sys::{ Error, Reflect };
+use wasm_bindgen::{ prelude::wasm_bindgen, JsValue };
+
+use crate::set_data::SetDataError;
+
+/**
+ * Errors surfaced to JS by TeamFinder
+ *
+ * Thrown as an Error whose `code` property is one of ISearchErrorCode,
+ * so the GUI can tell bad input apart from bugs without parsing messages
+ */
+#[derive(Debug, PartialEq)]
+pub enum SearchError {
+    // Options / cursor don't match the expected shape
+    MalformedOptions(String),
+    MalformedCursor(String),
+    CursorMismatch,
+    UnknownChampion(String),
+    TeamTooLarge {
+        team_size: u8,
+        num_champions: usize,
+    },
+    // Each slot needs a different team member
+    TooManySlots {
+        team_size: u8,
+        num_slots: usize,
+    },
+    // Index of the slot
+    EmptySlot(usize),
+    UnknownBackend(String),
+    InvalidPartition {
+        index: usize,
+        count: usize,
+    },
+    // Estimated before building the formula
+    SearchTooLarge {
+        clauses: usize,
+        max_clauses: usize,
+    },
+    SetData(SetDataError),
+    // Bug in the solver, eg a solution that can't be mapped back to a team
+    Internal(String),
+}
+
+impl SearchError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::MalformedOptions(_) => "MALFORMED_OPTIONS",
+            Self::MalformedCursor(_) => "MALFORMED_CURSOR",
+            Self::CursorMismatch => "CURSOR_MISMATCH",
+            Self::UnknownChampion(_) => "UNKNOWN_CHAMPION",
+            Self::TeamTooLarge { .. } => "TEAM_TOO_LARGE",
+            Self::TooManySlots { .. } => "TOO_MANY_SLOTS",
+            Self::EmptySlot(_) => "EMPTY_SLOT",
+            Self::UnknownBackend(_) => "UNKNOWN_BACKEND",
+            Self::InvalidPartition { .. } => "INVALID_PARTITION",
+            Self::SearchTooLarge { .. } => "SEARCH_TOO_LARGE",
+            Self::SetData(SetDataError::UnknownTrait { .. }) => {
+                "UNKNOWN_TRAIT"
+            }
+            Self::SetData(_) => "INVALID_SET_DATA",
+            Self::Internal(_) => "INTERNAL",
+        }
+    }
+}
+
+impl fmt::Display for SearchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MalformedOptions(e) => {
+                write!(f, "Malformed search options: {}", e)
+            }
+            Self::MalformedCursor(e) => {
+                write!(f, "Malformed search cursor: {}", e)
+            }
+            Self::CursorMismatch => {
+                write!(f, "Cursor was created with different search options")
+            }
+            Self::UnknownChampion(id) => {
+                write!(f, "Champion {} is not in the set data", id)
+            }
+            Self::TeamTooLarge { team_size, num_champions } => {
+                write!(
+                    f,
+                    "Team size {} is larger than the {} champions in the set",
+                    team_size,
+                    num_champions
+                )
+            }
+            Self::TooManySlots { team_size, num_slots } => {
+                write!(
+                    f,
+                    "{} slots can't be filled by a team of {}",
+                    num_slots,
+                    team_size
+                )
+            }
+            Self::EmptySlot(idx) => {
+                write!(f, "Slot {} has no champion options", idx + 1)
+            }
+            Self::UnknownBackend(name) => {
+                write!(f, "Unknown solver backend {}", name)
+            }
+            Self::InvalidPartition { index, count } => {
+                write!(
+                    f,
+                    "Partition {} is out of range for {} partitions",
+                    index,
+                    count
+                )
+            }
+            Self::SearchTooLarge { clauses, max_clauses } => {
+                write!(
+                    f,
+                    "Search is too large: about {} clauses, more than the limit of {}",
+                    clauses,
+                    max_clauses
+                )
+            }
+            Self::SetData(e) => write!(f, "{}", e),
+            Self::Internal(e) => write!(f, "Internal error: {}", e),
+        }
+    }
+}
+
+impl From<SetDataError> for SearchError {
+    fn from(e: SetDataError) -> Self {
+        Self::SetData(e)
+    }
+}
+
+impl From<SearchError> for JsValue {
+    fn from(e: SearchError) -> Self {
+        let error = Error::new(&e.to_string());
+        Reflect::set(&error, &"code".into(), &e.code().into()).ok();
+
+        error.into()
+    }
+}
+
+#[wasm_bindgen(typescript_custom_section)]
+const TYPES: &'static str =
+    r#"
+
+export type ISearchErrorCode =
+    | 'MALFORMED_OPTIONS'
+    | 'MALFORMED_CURSOR'
+    | 'CURSOR_MISMATCH'
+    | 'UNKNOWN_CHAMPION'
+    | 'TEAM_TOO_LARGE'
+    | 'TOO_MANY_SLOTS'
+    | 'EMPTY_SLOT'
+    | 'UNKNOWN_BACKEND'
+    | 'INVALID_PARTITION'
+    | 'SEARCH_TOO_LARGE'
+    | 'UNKNOWN_TRAIT'
+    | 'INVALID_SET_DATA'
+    | 'INTERNAL'
+
+export interface ISearchError extends Error {
+    code: ISearchErrorCode
+}
+
+"#;
@@ -0,0 +1,5 @@
+mod cursor;
+mod error;
+mod options_codec;
+mod search_teams;
+mod team;
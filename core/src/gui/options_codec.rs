@@ -0,0 +1,485 @@
+use std::collections::{ BTreeSet, HashMap };
+use std::io::{ Read, Write };
+
+use base64::{ engine::general_purpose::URL_SAFE_NO_PAD, Engine };
+use flate2::{
+    read::DeflateDecoder,
+    write::DeflateEncoder,
+    Compression,
+};
+use serde::Serialize;
+use wasm_bindgen::{ prelude::wasm_bindgen, JsValue };
+
+use crate::sat::ConstraintFamily;
+use crate::set_data::SetData;
+use super::error::SearchError;
+use super::search_teams::SearchOptions;
+
+/**
+ * Layout of the (uncompressed) version 1 encoding
+ *
+ *   version          u8
+ *   team_size        u8
+ *
+ *   num_champions    u16 (little endian)
+ *   champion ids     (u8 length + utf-8 bytes) for each champion
+ *                    in any slot, sorted
+ *
+ *   num_slots        u8
+ *   slot options     bitset over the champion table,
+ *                    ceil(num_champions / 8) bytes for each slot
+ *
+ *   optional fields  (u8 tag + u8 length + bytes) until the end
+ *
+ * Decoders skip optional fields with unknown tags (and unknown values in lists),
+ * so new optional fields can be added without bumping the version.
+ */
+const VERSION: u8 = 1;
+
+const TAG_DIVERSITY: u8 = 1;
+const TAG_BACKEND: u8 = 2;
+const TAG_DEBUG: u8 = 3;
+// Comma separated constraint family labels
+const TAG_DISABLED_CONSTRAINTS: u8 = 4;
+// u32 (little endian)
+const TAG_MAX_CLAUSES: u8 = 5;
+const TAG_MIN_SYNERGY: u8 = 6;
+
+#[wasm_bindgen]
+pub fn encode_options(options: JsValue) -> Result<String, SearchError> {
+    let options: SearchOptions = serde_wasm_bindgen
+        ::from_value(options)
+        .map_err(|e| SearchError::MalformedOptions(e.to_string()))?;
+
+    encode_search_options(&options).map_err(SearchError::MalformedOptions)
+}
+
+/**
+ * Options decoded against the set data that's loaded now,
+ * which may have changed since they were encoded (eg a new patch)
+ */
+#[derive(Debug, PartialEq, Serialize)]
+pub(super) struct DecodedOptions {
+    pub options: SearchOptions,
+    // Slot options the set data doesn't have (anymore), sorted,
+    // which are left out of the slots
+    pub unknown_champions: Vec<String>,
+}
+
+pub(super) fn decode_options_for(
+    encoded: &str,
+    set_data: &SetData
+) -> Result<DecodedOptions, String> {
+    let mut options = decode_search_options(encoded)?;
+    let is_known = |id: &String| set_data.index_of(id).is_some();
+
+    let unknown_champions = Vec::from_iter(
+        BTreeSet::from_iter(
+            options.slots.iter().flatten().filter(|id| !is_known(id))
+        )
+        .into_iter()
+        .cloned()
+    );
+    for slot in options.slots.iter_mut() {
+        slot.retain(is_known);
+    }
+
+    Ok(DecodedOptions { options, unknown_champions })
+}
+
+pub(super) fn encode_search_options(
+    options: &SearchOptions
+) -> Result<String, String> {
+    let mut writer = ByteWriter(vec![]);
+
+    writer.u8(VERSION);
+    writer.u8(options.team_size);
+
+    // Champions are referenced by their index in this table
+    let champion_ids = BTreeSet::from_iter(options.slots.iter().flatten());
+    let champion_indices = HashMap::<&String, usize>::from_iter(
+        champion_ids
+            .iter()
+            .enumerate()
+            .map(|(idx, id)| (*id, idx))
+    );
+
+    let num_champions = u16::try_from(champion_ids.len()).map_err(|_|
+        format!("Too many champions to encode ({})", champion_ids.len())
+    )?;
+    writer.bytes(&num_champions.to_le_bytes());
+    for id in champion_ids.iter() {
+        writer.len(id.len())?;
+        writer.bytes(id.as_bytes());
+    }
+
+    writer.len(options.slots.len())?;
+    for slot in options.slots.iter() {
+        let mut bitset = vec![0u8; bitset_len(champion_ids.len())];
+
+        for id in slot {
+            let idx = champion_indices[id];
+            bitset[idx / 8] |= 1 << (idx % 8);
+        }
+
+        writer.bytes(&bitset);
+    }
+
+    if let Some(diversity) = options.diversity {
+        writer.field(TAG_DIVERSITY, &[diversity])?;
+    }
+    if let Some(backend) = options.backend.as_ref() {
+        writer.field(TAG_BACKEND, backend.as_bytes())?;
+    }
+    if let Some(debug) = options.debug {
+        writer.field(TAG_DEBUG, &[debug as u8])?;
+    }
+    if let Some(families) = options.disabled_constraints.as_ref() {
+        let labels = Vec::from_iter(families.iter().map(|f| f.label()));
+        writer.field(
+            TAG_DISABLED_CONSTRAINTS,
+            labels.join(",").as_bytes()
+        )?;
+    }
+
+    if let Some(max_clauses) = options.max_clauses {
+        let max_clauses = u32::try_from(max_clauses).map_err(|_|
+            format!("Clause limit is too large to encode ({})", max_clauses)
+        )?;
+        writer.field(TAG_MAX_CLAUSES, &max_clauses.to_le_bytes())?;
+    }
+    if let Some(min_synergy) = options.min_synergy {
+        writer.field(TAG_MIN_SYNERGY, &[min_synergy])?;
+    }
+
+    let mut encoder = DeflateEncoder::new(vec![], Compression::best());
+    encoder.write_all(&writer.0).map_err(|e| e.to_string())?;
+    let compressed = encoder.finish().map_err(|e| e.to_string())?;
+
+    Ok(URL_SAFE_NO_PAD.encode(compressed))
+}
+
+pub(super) fn decode_search_options(
+    encoded: &str
+) -> Result<SearchOptions, String> {
+    let compressed = URL_SAFE_NO_PAD
+        .decode(encoded)
+        .map_err(|_| "Options are not valid base64url".to_string())?;
+
+    let mut bytes = vec![];
+    DeflateDecoder::new(compressed.as_slice())
+        .read_to_end(&mut bytes)
+        .map_err(|_| "Options could not be decompressed".to_string())?;
+
+    let mut reader = ByteReader { bytes: &bytes, pos: 0 };
+
+    let version = reader.u8()?;
+    if version == 0 {
+        return Err("Options have no version".to_string());
+    }
+    if version > VERSION {
+        return Err(
+            format!(
+                "Options were encoded by a newer version ({}) of the app",
+                version
+            )
+        );
+    }
+
+    let team_size = reader.u8()?;
+
+    let num_champions = u16::from_le_bytes([reader.u8()?, reader.u8()?]);
+    let mut champion_ids = Vec::<String>::new();
+    for _ in 0..num_champions {
+        let len = reader.u8()? as usize;
+        let id = String::from_utf8(reader.bytes(len)?.to_vec())
+            .map_err(|_| "Champion id is not valid utf-8".to_string())?;
+
+        champion_ids.push(id);
+    }
+
+    let num_slots = reader.u8()?;
+    let mut slots = vec![];
+    for _ in 0..num_slots {
+        let bitset = reader.bytes(bitset_len(champion_ids.len()))?;
+
+        slots.push(
+            champion_ids
+                .iter()
+                .enumerate()
+                .filter(|(idx, _)| (bitset[idx / 8] >> (idx % 8)) & 1 == 1)
+                .map(|(_, id)| id.clone())
+                .collect()
+        );
+    }
+
+    let mut options = SearchOptions {
+        team_size,
+        slots,
+        diversity: None,
+        backend: None,
+        disabled_constraints: None,
+        max_clauses: None,
+        min_synergy: None,
+        debug: None,
+    };
+
+    while !reader.is_done() {
+        let tag = reader.u8()?;
+        let len = reader.u8()? as usize;
+        let value = reader.bytes(len)?;
+
+        match (tag, value) {
+            (TAG_DIVERSITY, [diversity]) => {
+                options.diversity = Some(*diversity);
+            }
+            (TAG_BACKEND, name) => {
+                options.backend = Some(
+                    String::from_utf8_lossy(name).into_owned()
+                );
+            }
+            (TAG_DEBUG, [debug]) => {
+                options.debug = Some(*debug != 0);
+            }
+            (TAG_DISABLED_CONSTRAINTS, labels) => {
+                let labels = String::from_utf8_lossy(labels);
+
+                // Families from a newer version are skipped
+                options.disabled_constraints = Some(
+                    Vec::from_iter(
+                        labels.split(',').filter_map(ConstraintFamily::from_label)
+                    )
+                );
+            }
+            (TAG_MAX_CLAUSES, [a, b, c, d]) => {
+                options.max_clauses = Some(
+                    u32::from_le_bytes([*a, *b, *c, *d]) as usize
+                );
+            }
+            (TAG_MIN_SYNERGY, [min_synergy]) => {
+                options.min_synergy = Some(*min_synergy);
+            }
+            // Field from a newer version
+            _ => {}
+        }
+    }
+
+    Ok(options)
+}
+
+fn bitset_len(num_champions: usize) -> usize {
+    num_champions.div_ceil(8)
+}
+
+struct ByteWriter(Vec<u8>);
+
+impl ByteWriter {
+    fn u8(&mut self, value: u8) {
+        self.0.push(value);
+    }
+
+    fn len(&mut self, len: usize) -> Result<(), String> {
+        let len = u8::try_from(len).map_err(|_|
+            format!("Too many items to encode ({})", len)
+        )?;
+
+        self.u8(len);
+        Ok(())
+    }
+
+    fn bytes(&mut self, bytes: &[u8]) {
+        self.0.extend_from_slice(bytes);
+    }
+
+    fn field(&mut self, tag: u8, value: &[u8]) -> Result<(), String> {
+        self.u8(tag);
+        self.len(value.len())?;
+        self.bytes(value);
+        Ok(())
+    }
+}
+
+struct ByteReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let end = self.pos + len;
+        if end > self.bytes.len() {
+            return Err("Options are truncated".to_string());
+        }
+
+        let result = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(result)
+    }
+
+    fn is_done(&self) -> bool {
+        self.pos >= self.bytes.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use base64::{ engine::general_purpose::URL_SAFE_NO_PAD, Engine };
+    use flate2::{ write::DeflateEncoder, Compression };
+
+    use crate::sat::ConstraintFamily;
+    use crate::set_data::{
+        ChampionData,
+        ChampionStats,
+        DamageType,
+        SetData,
+    };
+    use super::{
+        decode_options_for,
+        decode_search_options,
+        encode_search_options,
+    };
+    use super::super::search_teams::SearchOptions;
+
+    fn ids(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    /**
+     * Champions without any traits or stats
+     */
+    fn set_data(champion_ids: &[&str]) -> SetData {
+        let champions = Vec::from_iter(
+            champion_ids.iter().map(|id| ChampionData {
+                id: id.to_string(),
+                display_name: id.to_string(),
+                cost: 1,
+                traits: vec![],
+                damage_type: DamageType { is_ad: true, is_ap: false },
+                stats: ChampionStats {
+                    damage: None,
+                    mana: None,
+                    range: None,
+                },
+            })
+        );
+
+        SetData::new(champions, vec![]).unwrap()
+    }
+
+    /**
+     * Encoding of raw (uncompressed) bytes
+     */
+    fn compress(bytes: &[u8]) -> String {
+        let mut encoder = DeflateEncoder::new(vec![], Compression::best());
+        encoder.write_all(bytes).unwrap();
+        URL_SAFE_NO_PAD.encode(encoder.finish().unwrap())
+    }
+
+    fn example() -> SearchOptions {
+        SearchOptions {
+            team_size: 3,
+            // Sorted, since that's the order they're decoded in
+            slots: vec![
+                ids(&["TFT11_Ahri", "TFT11_Azir", "TFT11_Yone"]),
+                vec![],
+                ids(&["TFT11_Azir", "TFT11_Kobuko"]),
+            ],
+            diversity: Some(2),
+            backend: Some("cdcl".to_string()),
+            disabled_constraints: Some(
+                vec![
+                    ConstraintFamily::Connectivity,
+                    ConstraintFamily::SlotDedupe,
+                ]
+            ),
+            max_clauses: Some(100_000),
+            min_synergy: Some(2),
+            debug: None,
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let options = example();
+
+        let encoded = encode_search_options(&options).unwrap();
+        let is_url_safe = |c: char| {
+            c.is_ascii_alphanumeric() || c == '-' || c == '_'
+        };
+        assert!(encoded.chars().all(is_url_safe));
+
+        assert_eq!(decode_search_options(&encoded).unwrap(), options);
+    }
+
+    #[test]
+    fn test_invalid() {
+        let mut options = example();
+        options.slots.push(vec!["x".repeat(256)]);
+        assert!(encode_search_options(&options).is_err());
+
+        assert!(decode_search_options("not base64!").is_err());
+
+        // Truncated payload
+        let encoded = encode_search_options(&example()).unwrap();
+        let truncated = &encoded[..encoded.len() / 2];
+        assert!(decode_search_options(truncated).is_err());
+
+        // Versions start at 1
+        assert!(decode_search_options(&compress(&[0, 2, 0, 0, 0])).is_err());
+        assert!(decode_search_options(&compress(&[1, 2, 0, 0, 0])).is_ok());
+    }
+
+    #[test]
+    fn test_skip_unknown() {
+        let disabled = b"connectivity,from_the_future";
+
+        #[rustfmt::skip]
+        let mut bytes = vec![
+            1, 2,
+            // no champions or slots
+            0, 0, 0,
+            // field from a newer version
+            99, 2, 1, 2,
+        ];
+        bytes.extend([4, disabled.len() as u8]);
+        bytes.extend(disabled);
+
+        let options = decode_search_options(&compress(&bytes)).unwrap();
+        assert_eq!(options.team_size, 2);
+        assert_eq!(
+            options.disabled_constraints,
+            Some(vec![ConstraintFamily::Connectivity])
+        );
+    }
+
+    #[test]
+    fn test_set_data_changes() {
+        let encoded = encode_search_options(&example()).unwrap();
+
+        let current = set_data(
+            &["TFT11_Ahri", "TFT11_Azir", "TFT11_Kobuko", "TFT11_Yone"]
+        );
+        let decoded = decode_options_for(&encoded, &current).unwrap();
+        assert_eq!(decoded.options, example());
+        assert!(decoded.unknown_champions.is_empty());
+
+        // A patch removed two champions and added one
+        let patched = set_data(&["TFT11_Yone", "TFT11_Ahri", "TFT11_Lux"]);
+        let decoded = decode_options_for(&encoded, &patched).unwrap();
+        assert_eq!(
+            decoded.unknown_champions,
+            ids(&["TFT11_Azir", "TFT11_Kobuko"])
+        );
+        assert_eq!(
+            decoded.options.slots,
+            vec![ids(&["TFT11_Ahri", "TFT11_Yone"]), vec![], vec![]]
+        );
+        assert_eq!(decoded.options.team_size, 3);
+    }
+}
@@ -0,0 +1,1660 @@
+use std::collections::HashSet;
+use web_time::Instant;
+
+use js_sys::Uint32Array;
+use serde::{ Deserialize, Serialize };
+use serde_wasm_bindgen::to_value;
+use wasm_bindgen::{ prelude::wasm_bindgen, JsValue };
+
+use crate::console::log;
+use crate::set_data::SetData;
+use crate::team_mask::TeamMask;
+use crate::trait_graph::TraitGraph;
+use crate::trait_hypergraph::TraitHypergraph;
+use crate::sat::{
+    build_champion_constraints,
+    build_champion_graphs,
+    write_dimacs,
+    CdclBackend,
+    ChampionConstraints,
+    ConstraintFamily,
+    ConstraintStats,
+    MiniSatBackend,
+    PruneReason,
+    SolverProgress,
+    SubgraphEnumerator,
+    SubgraphSearch,
+    SubgraphSolver,
+    TeamSolverBackend,
+};
+use super::cursor::{ Fnv64, SearchCursor };
+use super::error::SearchError;
+use super::options_codec::decode_options_for;
+use super::team::{ Team, TeamTrait };
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub(super) struct SearchOptions {
+    pub team_size: u8,
+    // Champion ids (eg TFT11_Ahri) allowed in each slot
+    // With a slot per team member, champions outside every slot
+    // (eg ones banned globally) are pruned before anything is encoded,
+    // with fewer slots the remaining members can be any champion
+    pub slots: Vec<Vec<String>>,
+
+    // Minimum number of champions each result must differ by from all previous results
+    pub diversity: Option<u8>,
+
+    // SAT solver implementation, "minisat" (default) or "cdcl"
+    // Searches that only need connectivity skip the SAT solver,
+    // unless a backend is set explicitly
+    pub backend: Option<String>,
+
+    // Constraint families to leave out, for debugging
+    // eg without connectivity, any champions matching the slots form a team
+    pub disabled_constraints: Option<Vec<ConstraintFamily>>,
+
+    // Ceiling on the estimated size of the formula, see DEFAULT_MAX_CLAUSES
+    pub max_clauses: Option<usize>,
+
+    // Minimum number of other team members each champion shares a trait with,
+    // so eg a chain of champions that each only share a trait with the next is ruled out
+    pub min_synergy: Option<u8>,
+
+    // Logs how the search was built and every team it returns
+    pub debug: Option<bool>,
+}
+
+/**
+ * Large enough for any realistic set,
+ * small enough that building the formula doesn't hang the worker
+ */
+const DEFAULT_MAX_CLAUSES: usize = 2_000_000;
+
+impl SearchOptions {
+    /**
+     * Catches options that would otherwise produce
+     * a nonsensical formula or a panic while building it
+     */
+    fn validate(&self, set_data: &SetData) -> Result<(), SearchError> {
+        let num_champions = set_data.champions().len();
+
+        if (self.team_size as usize) > num_champions {
+            return Err(SearchError::TeamTooLarge {
+                team_size: self.team_size,
+                num_champions,
+            });
+        }
+
+        if self.slots.len() > self.team_size as usize {
+            return Err(SearchError::TooManySlots {
+                team_size: self.team_size,
+                num_slots: self.slots.len(),
+            });
+        }
+
+        for (idx, slot) in self.slots.iter().enumerate() {
+            if slot.is_empty() {
+                return Err(SearchError::EmptySlot(idx));
+            }
+
+            for id in slot {
+                resolve_champion(set_data, id)?;
+            }
+        }
+
+        select_backend(self.backend.as_deref())?;
+
+        Ok(())
+    }
+
+    /**
+     * Hash of every option that affects which teams are found
+     * (ie everything except debug and the backend),
+     * plus the champions and traits of the set data the slots refer to
+     *
+     * Doesn't depend on the order of the champions in the set data,
+     * so cursors survive champions being reordered between patches
+     */
+    fn fingerprint(&self, set_data: &SetData) -> String {
+        let mut hasher = Fnv64::new();
+
+        // Values that behave the same hash the same,
+        // eg a diversity of 0 or 1 is the same as none
+        hasher.write(&[self.team_size]);
+        hasher.write(&[self.diversity.unwrap_or(1).max(1)]);
+
+        // Only hashed when they change the search, so older cursors stay valid
+        let disabled = self.disabled_families();
+        if !disabled.is_empty() {
+            hasher.write(&(disabled.len() as u32).to_le_bytes());
+            for family in disabled {
+                hasher.write(family.label().as_bytes());
+                hasher.write(&[0]);
+            }
+        }
+
+        if let Some(min_synergy) = self.min_synergy.filter(|m| *m > 0) {
+            hasher.write(b"min_synergy");
+            hasher.write(&[min_synergy]);
+        }
+
+        for slot in self.slots.iter() {
+            let mut slot = slot.clone();
+            slot.sort();
+
+            hasher.write(&(slot.len() as u32).to_le_bytes());
+            for id in slot {
+                hasher.write(&(id.len() as u32).to_le_bytes());
+                hasher.write(id.as_bytes());
+            }
+        }
+
+        let mut champions = Vec::from_iter(set_data.champions().iter());
+        champions.sort_by(|a, b| a.id.cmp(&b.id));
+
+        for champion in champions {
+            let mut traits = Vec::from_iter(
+                champion.traits.iter().map(|t| &t.id)
+            );
+            traits.sort();
+
+            hasher.write(&(champion.id.len() as u32).to_le_bytes());
+            hasher.write(champion.id.as_bytes());
+            hasher.write(&(traits.len() as u32).to_le_bytes());
+            for id in traits {
+                hasher.write(&(id.len() as u32).to_le_bytes());
+                hasher.write(id.as_bytes());
+            }
+        }
+
+        hasher.finish()
+    }
+
+    /**
+     * Sorted, without duplicates
+     */
+    fn disabled_families(&self) -> Vec<ConstraintFamily> {
+        let mut families = self.disabled_constraints
+            .clone()
+            .unwrap_or_default();
+        families.sort();
+        families.dedup();
+
+        families
+    }
+}
+
+/**
+ * Solvers for the current options, one per connected component
+ * of the trait graph that could contain a team
+ */
+struct Search {
+    components: Vec<ComponentSearch>,
+    pruned: Vec<(usize, PruneReason)>,
+    // Totals over every component
+    stats: ConstraintStats,
+    // Components take turns returning teams, starting from this one
+    next_component: usize,
+    started_at: Instant,
+}
+
+struct ComponentSearch {
+    solver: Box<dyn SubgraphSearch>,
+    // Champion index of each vertex, ascending
+    champions: Vec<usize>,
+    is_exhausted: bool,
+}
+
+impl ComponentSearch {
+    fn vertex_of(&self, champion: usize) -> Option<usize> {
+        self.champions.binary_search(&champion).ok()
+    }
+}
+
+impl Search {
+    /**
+     * No components, ie no teams until the next reset
+     */
+    fn empty() -> Self {
+        Self {
+            components: vec![],
+            pruned: vec![],
+            stats: ConstraintStats::default(),
+            next_component: 0,
+            started_at: Instant::now(),
+        }
+    }
+
+    /**
+     * Champion indices of the next team, ascending
+     */
+    fn next(&mut self) -> Option<Vec<usize>> {
+        let num_components = self.components.len();
+
+        for offset in 0..num_components {
+            let idx = (self.next_component + offset) % num_components;
+            let component = &mut self.components[idx];
+            if component.is_exhausted {
+                continue;
+            }
+
+            match component.solver.next() {
+                Some(vertices) => {
+                    self.next_component = idx + 1;
+
+                    return Some(
+                        Vec::from_iter(
+                            vertices.iter().map(|v| component.champions[*v])
+                        )
+                    );
+                }
+                None => {
+                    component.is_exhausted = true;
+                }
+            }
+        }
+
+        None
+    }
+
+    /**
+     * False if no component contains the whole team,
+     * so it can't be one this search returns
+     */
+    fn block_team(&mut self, champions: &[usize]) -> bool {
+        for component in self.components.iter_mut() {
+            let vertices: Option<Vec<usize>> = champions
+                .iter()
+                .map(|c| component.vertex_of(*c))
+                .collect();
+
+            if let Some(vertices) = vertices {
+                component.solver.block_solution(&vertices);
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /**
+     * Blocks the teams of a cursor created with the same options,
+     * returning them as masks without duplicates
+     */
+    fn restore(
+        &mut self,
+        set_data: &SetData,
+        options: &SearchOptions,
+        cursor: SearchCursor
+    ) -> Result<Vec<TeamMask>, SearchError> {
+        let mut teams = vec![];
+        let mut seen = HashSet::<TeamMask>::new();
+
+        for team in cursor.teams {
+            let champions = team
+                .iter()
+                .map(|id| resolve_champion(set_data, id))
+                .collect::<Result<Vec<usize>, SearchError>>()?;
+
+            let mask = TeamMask::from_iter(&champions);
+            if !seen.insert(mask.clone()) {
+                continue;
+            }
+
+            // Stale or edited, since this search could never return it
+            if
+                mask.len() != options.team_size as usize ||
+                !self.block_team(&champions)
+            {
+                return Err(SearchError::MalformedCursor(format!(
+                    "{} is not a team of {} this search can return",
+                    team.join(", "),
+                    options.team_size
+                )));
+            }
+
+            teams.push(mask);
+        }
+
+        Ok(teams)
+    }
+
+    /**
+     * Champion indices that are in every team, and in none (both ascending)
+     *
+     * A team is within one component, so champions are only forced in
+     * if a single component has any teams. Champions outside every component
+     * (eg pruned ones) are always forced out.
+     */
+    fn backbone(&self, num_champions: usize) -> (Vec<usize>, Vec<usize>) {
+        let mut is_possible = vec![false; num_champions];
+        let mut forced_in = vec![];
+        let mut num_satisfiable = 0;
+
+        for component in self.components.iter() {
+            let Some(backbone) = component.solver.backbone() else {
+                continue;
+            };
+            num_satisfiable += 1;
+
+            for (v, champion) in component.champions.iter().enumerate() {
+                if backbone.forced_out.binary_search(&v).is_err() {
+                    is_possible[*champion] = true;
+                }
+            }
+
+            forced_in = Vec::from_iter(
+                backbone.forced_in.iter().map(|v| component.champions[*v])
+            );
+        }
+
+        if num_satisfiable != 1 {
+            forced_in = vec![];
+        }
+
+        let forced_out = Vec::from_iter(
+            (0..num_champions).filter(|c| !is_possible[*c])
+        );
+
+        (forced_in, forced_out)
+    }
+
+    /**
+     * Sums the progress of each component,
+     * weighting their fractions by the size of their search spaces
+     */
+    fn progress(&self) -> SearchProgress {
+        let total = self.components
+            .iter()
+            .map(|c| c.solver.progress())
+            .reduce(|a, b| SolverProgress {
+                solutions_found: a.solutions_found + b.solutions_found,
+                conflicts: a.conflicts.zip(b.conflicts).map(|(a, b)| a + b),
+                elapsed_ms: a.elapsed_ms.max(b.elapsed_ms),
+                searched: a.searched + b.searched,
+                search_space: a.search_space + b.search_space,
+            });
+
+        SearchProgress {
+            teams_found: total.as_ref().map_or(0, |p| p.solutions_found),
+            conflicts: total.as_ref().and_then(|p| p.conflicts),
+            elapsed_ms: self.started_at.elapsed().as_millis() as u64,
+            fraction_searched: total.map_or(1.0, |p| p.fraction_searched()),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct SearchProgress {
+    // Since the last reset, excluding teams restored from a cursor
+    teams_found: usize,
+    conflicts: Option<u64>,
+    elapsed_ms: u64,
+    fraction_searched: f64,
+}
+
+#[derive(Serialize)]
+struct SimilarTeam {
+    champion_ids: Vec<String>,
+    // Jaccard similarity, from 0 to 1
+    similarity: f64,
+}
+
+#[derive(Serialize)]
+struct Backbone {
+    // Champion ids in every team matching the options
+    forced_in: Vec<String>,
+    // Champion ids in none of them
+    forced_out: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct PrunedChampion {
+    id: String,
+    reason: PruneReason,
+}
+
+#[wasm_bindgen]
+pub struct TeamFinder {
+    set_data: SetData,
+    // Derived from the set data
+    traits: TraitHypergraph,
+    options: SearchOptions,
+    search: Search,
+    // Teams returned since the last reset (including any restored from a cursor),
+    // as masks of champion indices
+    history: Vec<TeamMask>,
+}
+
+#[wasm_bindgen]
+impl TeamFinder {
+    pub fn new() -> Self {
+        let set_data = SetData::default();
+        let options = SearchOptions {
+            team_size: 1,
+            slots: vec![],
+            diversity: None,
+            backend: None,
+            disabled_constraints: None,
+            max_clauses: None,
+            min_synergy: None,
+            debug: Some(false),
+        };
+
+        // Default backend can't fail
+        let search = init_search(&set_data, &options).unwrap();
+
+        Self {
+            traits: TraitHypergraph::from_set_data(&set_data),
+            set_data,
+            options,
+            search,
+            history: vec![],
+        }
+    }
+
+    /**
+     * Contents of merged_teamplanner_data.json and traits.json
+     * Must be called before reset(), slots refer to champions by their id in this data
+     *
+     * Ends the current search, since its teams refer to the previous champions
+     */
+    pub fn load_set_data(
+        &mut self,
+        champions_json: &str,
+        traits_json: &str
+    ) -> Result<(), SearchError> {
+        self.set_data = SetData::from_json(champions_json, traits_json)?;
+        self.traits = TraitHypergraph::from_set_data(&self.set_data);
+        self.search = Search::empty();
+        self.history = vec![];
+
+        Ok(())
+    }
+
+    /**
+     * Options from encode_options(), decoded against the loaded set data
+     * so champions that are no longer in it are dropped from the slots
+     * (IDecodedOptions)
+     */
+    pub fn decode_options(
+        &self,
+        encoded: &str
+    ) -> Result<JsValue, SearchError> {
+        let decoded = decode_options_for(encoded, &self.set_data).map_err(
+            SearchError::MalformedOptions
+        )?;
+
+        to_value(&decoded).map_err(|e| SearchError::Internal(e.to_string()))
+    }
+
+    /**
+     * If a cursor from a previous search with the same options is passed in,
+     * the teams it already returned are skipped
+     *
+     * Returns the size of the encoding (IConstraintStats)
+     */
+    pub fn reset(
+        &mut self,
+        options: JsValue,
+        cursor: JsValue
+    ) -> Result<JsValue, SearchError> {
+        let options: SearchOptions = serde_wasm_bindgen
+            ::from_value(options)
+            .map_err(|e| SearchError::MalformedOptions(e.to_string()))?;
+
+        options.validate(&self.set_data)?;
+
+        let cursor: Option<SearchCursor> = if
+            cursor.is_undefined() ||
+            cursor.is_null()
+        {
+            None
+        } else {
+            let cursor: SearchCursor = serde_wasm_bindgen
+                ::from_value(cursor)
+                .map_err(|e|
+                    SearchError::MalformedCursor(e.to_string())
+                )?;
+
+            let options_hash = options.fingerprint(&self.set_data);
+            if cursor.options_hash != options_hash {
+                return Err(SearchError::CursorMismatch);
+            }
+
+            Some(cursor)
+        };
+
+        // Only replaced once the cursor checks out
+        let mut search = init_search(&self.set_data, &options)?;
+        let history = match cursor {
+            Some(cursor) => search.restore(&self.set_data, &options, cursor)?,
+            None => vec![],
+        };
+
+        self.search = search;
+        self.history = history;
+        self.options = options;
+
+        to_value(&self.search.stats).map_err(|e|
+            SearchError::Internal(e.to_string())
+        )
+    }
+
+    /**
+     * Like reset(), but only searches slice `index` of `count` disjoint slices of the teams,
+     * so the search can be split across web workers that each call this with the same options
+     *
+     * The cursor can be the merged cursors of every partition,
+     * teams in other slices are skipped anyway
+     */
+    pub fn reset_partition(
+        &mut self,
+        options: JsValue,
+        cursor: JsValue,
+        index: usize,
+        count: usize
+    ) -> Result<JsValue, SearchError> {
+        if index >= count {
+            return Err(SearchError::InvalidPartition { index, count });
+        }
+
+        let stats = self.reset(options, cursor)?;
+
+        for component in self.search.components.iter_mut() {
+            component.solver.partition(index, count);
+        }
+
+        Ok(stats)
+    }
+
+    pub fn cursor(&self) -> Result<JsValue, SearchError> {
+        to_value(&self.search_cursor()).map_err(|e|
+            SearchError::Internal(e.to_string())
+        )
+    }
+
+    /**
+     * Teams returned so far as bitsets over the champions of the set data,
+     * TeamFinder.mask_words() words per team
+     * (champion i is bit i % 32 of word i / 32)
+     */
+    pub fn team_masks(&self) -> Uint32Array {
+        let num_words = self.mask_words();
+        let words = Vec::from_iter(
+            self.history
+                .iter()
+                .flat_map(|mask| mask.to_u32_words(num_words))
+        );
+
+        Uint32Array::from(words.as_slice())
+    }
+
+    pub fn mask_words(&self) -> usize {
+        TeamMask::num_u32_words(self.set_data.champions().len())
+    }
+
+    /**
+     * Up to `count` of the teams returned so far,
+     * most similar to these champions first (ISimilarTeam[])
+     */
+    pub fn similar(
+        &self,
+        champion_ids: Vec<String>,
+        count: usize
+    ) -> Result<JsValue, SearchError> {
+        let target = champion_ids
+            .iter()
+            .map(|id| resolve_champion(&self.set_data, id))
+            .collect::<Result<TeamMask, SearchError>>()?;
+
+        let mut ranked = Vec::from_iter(
+            self.history.iter().map(|mask| (mask, mask.jaccard(&target)))
+        );
+        // Stable, so ties stay in the order they were found
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        let similar = Vec::from_iter(
+            ranked
+                .into_iter()
+                .take(count)
+                .map(|(mask, similarity)| SimilarTeam {
+                    champion_ids: self.champion_ids(mask),
+                    similarity,
+                })
+        );
+
+        to_value(&similar).map_err(|e|
+            SearchError::Internal(e.to_string())
+        )
+    }
+
+    /**
+     * Traits of a team and which of its champions have them (ITeamTrait[]),
+     * eg to show why the champions of a team are connected
+     */
+    pub fn team_traits(
+        &self,
+        champion_ids: Vec<String>
+    ) -> Result<JsValue, SearchError> {
+        let champions = champion_ids
+            .iter()
+            .map(|id| resolve_champion(&self.set_data, id))
+            .collect::<Result<Vec<usize>, SearchError>>()?;
+
+        let traits = TeamTrait::of_team(
+            &champions,
+            &self.set_data,
+            &self.traits
+        );
+
+        to_value(&traits).map_err(|e|
+            SearchError::Internal(e.to_string())
+        )
+    }
+
+    /**
+     * Every champion of the set data and the traits they share (ITraitGraph),
+     * regardless of the current search
+     */
+    pub fn trait_graph(&self) -> Result<JsValue, SearchError> {
+        to_value(&TraitGraph::new(&self.set_data)).map_err(|e|
+            SearchError::Internal(e.to_string())
+        )
+    }
+
+    /**
+     * Number of connected components of the trait graph being searched,
+     * each has its own solver
+     */
+    pub fn num_components(&self) -> usize {
+        self.search.components.len()
+    }
+
+    /**
+     * CNF of one component of the current search in DIMACS format, for reproducing slow searches in other SAT tools
+     * (excludes the clauses blocking teams that were already returned)
+     *
+     * Undefined if the component isn't searched with a SAT solver
+     */
+    pub fn dimacs(&self, component: usize) -> Option<String> {
+        self.search.components
+            .get(component)
+            .and_then(|c| c.solver.constraints())
+            .map(write_dimacs)
+    }
+
+    /**
+     * Champions that were left out of the current search
+     * because they can't be in any team matching the options
+     */
+    pub fn pruned(&self) -> Result<JsValue, SearchError> {
+        let pruned = Vec::from_iter(
+            self.search.pruned
+                .iter()
+                .map(|(champion, reason)| PrunedChampion {
+                    id: self.set_data.champions()[*champion].id.clone(),
+                    reason: *reason,
+                })
+        );
+
+        to_value(&pruned).map_err(|e|
+            SearchError::Internal(e.to_string())
+        )
+    }
+
+    /**
+     * Champions that are in every team matching the options, or in none (IBackbone),
+     * eg to grey out champions the current filters rule out
+     * (teams that were already returned still count)
+     */
+    pub fn backbone(&self) -> Result<JsValue, SearchError> {
+        let (forced_in, forced_out) = self.search.backbone(
+            self.set_data.champions().len()
+        );
+
+        let ids = |champions: Vec<usize>| Vec::from_iter(
+            champions
+                .into_iter()
+                .map(|c| self.set_data.champions()[c].id.clone())
+        );
+        let backbone = Backbone {
+            forced_in: ids(forced_in),
+            forced_out: ids(forced_out),
+        };
+
+        to_value(&backbone).map_err(|e|
+            SearchError::Internal(e.to_string())
+        )
+    }
+
+    /**
+     * Can be polled between calls to next() to show how far along the search is
+     */
+    pub fn progress(&self) -> Result<JsValue, SearchError> {
+        to_value(&self.search.progress()).map_err(|e|
+            SearchError::Internal(e.to_string())
+        )
+    }
+
+    pub fn next(&mut self) -> Result<JsValue, SearchError> {
+        match self.search.next() {
+            Some(champions) => {
+                let mask = TeamMask::from_iter(&champions);
+                let team = Team::new(champions, &self.set_data)?;
+
+                if self.options.debug.unwrap_or(false) {
+                    log!("{:?}", team);
+                }
+
+                self.history.push(mask);
+
+                to_value(&team.champion_ids).map_err(|e|
+                    SearchError::Internal(e.to_string())
+                )
+            }
+            None => Ok(JsValue::null()),
+        }
+    }
+}
+
+impl TeamFinder {
+    fn search_cursor(&self) -> SearchCursor {
+        SearchCursor {
+            options_hash: self.options.fingerprint(&self.set_data),
+            teams: Vec::from_iter(
+                self.history.iter().map(|mask| self.champion_ids(mask))
+            ),
+        }
+    }
+
+    fn champion_ids(&self, mask: &TeamMask) -> Vec<String> {
+        Vec::from_iter(
+            mask.iter().map(|idx| self.set_data.champions()[idx].id.clone())
+        )
+    }
+}
+
+/**
+ * Champion id -> vertex index
+ */
+fn resolve_champion(
+    set_data: &SetData,
+    id: &str
+) -> Result<usize, SearchError> {
+    set_data
+        .index_of(id)
+        .ok_or_else(|| SearchError::UnknownChampion(id.to_string()))
+}
+
+fn select_backend(
+    name: Option<&str>
+) -> Result<Box<dyn TeamSolverBackend>, SearchError> {
+    match name {
+        None | Some("minisat") => Ok(Box::new(MiniSatBackend::new())),
+        Some("cdcl") => Ok(Box::new(CdclBackend::new())),
+        Some(name) => Err(SearchError::UnknownBackend(name.to_string())),
+    }
+}
+
+fn init_search(
+    set_data: &SetData,
+    options: &SearchOptions
+) -> Result<Search, SearchError> {
+    let start = Instant::now();
+    let debug = options.debug.unwrap_or(false);
+
+    if debug {
+        log!("Setting solver options {:?}", options);
+    }
+
+    let slots = options.slots
+        .iter()
+        .map(|slot|
+            slot
+                .iter()
+                .map(|id| resolve_champion(set_data, id))
+                .collect::<Result<Vec<usize>, SearchError>>()
+        )
+        .collect::<Result<Vec<_>, SearchError>>()?;
+
+    let disabled = options.disabled_families();
+
+    // Plain enumeration of connected teams doesn't need a SAT solver
+    let enumerate_directly =
+        slots.is_empty() &&
+        options.diversity.unwrap_or(1) <= 1 &&
+        options.min_synergy.unwrap_or(0) == 0 &&
+        options.backend.is_none() &&
+        disabled.is_empty();
+
+    if enumerate_directly {
+        let graphs = build_champion_graphs(
+            set_data,
+            options.team_size,
+            &slots,
+            &disabled
+        );
+
+        if debug {
+            log!(
+                "[{}ms] Enumerating {} components directly ({} champions pruned)",
+                start.elapsed().as_millis(),
+                graphs.components.len(),
+                graphs.pruned.len()
+            );
+        }
+
+        let components = Vec::from_iter(
+            graphs.components
+                .into_iter()
+                .map(|graph| ComponentSearch {
+                    solver: Box::new(
+                        SubgraphEnumerator::new(
+                            graph.champions.len(),
+                            options.team_size as usize,
+                            &graph.edges
+                        )
+                    ) as Box<dyn SubgraphSearch>,
+                    champions: graph.champions,
+                    is_exhausted: false,
+                })
+        );
+
+        // No formula, so the stats stay empty
+        return Ok(Search {
+            components,
+            pruned: graphs.pruned,
+            stats: ConstraintStats::default(),
+            next_component: 0,
+            started_at: Instant::now(),
+        });
+    }
+
+    let ChampionConstraints { components, pruned, encoding, estimate } =
+        build_champion_constraints(
+            set_data,
+            options.team_size,
+            &slots,
+            options.min_synergy.unwrap_or(0) as usize,
+            &disabled,
+            Some(options.max_clauses.unwrap_or(DEFAULT_MAX_CLAUSES))
+        ).map_err(|e| SearchError::SearchTooLarge {
+            clauses: e.estimate.clauses,
+            max_clauses: e.max_clauses,
+        })?;
+
+    if debug {
+        log!(
+            "Using {:?} connectivity encoding, estimated {} clauses over {} variables",
+            encoding,
+            estimate.clauses,
+            estimate.variables
+        );
+
+        log!(
+            "[{}ms] Solving {} components with {} constraints ({} champions pruned)",
+            start.elapsed().as_millis(),
+            components.len(),
+            components
+                .iter()
+                .map(|c| c.constraints.num_constraints())
+                .sum::<usize>(),
+            pruned.len()
+        );
+    }
+
+    let mut search = Search {
+        components: vec![],
+        pruned,
+        stats: ConstraintStats::default(),
+        next_component: 0,
+        started_at: Instant::now(),
+    };
+
+    for component in components {
+        let constraints = component.constraints;
+
+        let stats = constraints.stats();
+        if debug {
+            for family in stats.families.iter() {
+                log!(
+                    "{}: {} constraints, {} clauses, {} literals",
+                    family.family,
+                    family.constraints,
+                    family.clauses,
+                    family.literals
+                );
+            }
+        }
+        search.stats.merge(&stats);
+
+        let backend = select_backend(options.backend.as_deref())?;
+
+        let mut solver = SubgraphSolver::new_with_backend(
+            constraints,
+            backend
+        );
+        solver.diversity = options.diversity.unwrap_or(1) as usize;
+
+        search.components.push(ComponentSearch {
+            solver: Box::new(solver),
+            champions: component.champions,
+            is_exhausted: false,
+        });
+    }
+
+    if debug {
+        log!(
+            "[{}ms] {} CNF clauses over {} variables were generated",
+            start.elapsed().as_millis(),
+            search.stats.clauses,
+            search.stats.variables
+        );
+    }
+
+    Ok(search)
+}
+
+#[wasm_bindgen(typescript_custom_section)]
+const TYPES: &'static str =
+    r#"
+
+export interface ISearchTeamsOptions {
+    team_size: number
+    // Champion ids, eg TFT11_Ahri
+    // With fewer slots than team_size the remaining members can be any champion
+    slots: Array<string[]>
+
+    diversity?: number
+
+    // Searches that only need connectivity skip the SAT solver,
+    // unless a backend is set explicitly
+    backend?: 'minisat' | 'cdcl'
+
+    // For debugging, eg without 'connectivity' any champions matching the slots form a team
+    disabled_constraints?: IConstraintFamily[]
+
+    // Estimated clauses above which a cheaper encoding is used,
+    // or SEARCH_TOO_LARGE is thrown if that's still too large
+    max_clauses?: number
+
+    // Minimum number of other team members each champion shares a trait with
+    min_synergy?: number
+
+    debug?: boolean
+}
+
+export interface IDecodedOptions {
+    options: ISearchTeamsOptions
+    // Slot options that aren't in the loaded set data, eg after a patch,
+    // which were left out of the slots
+    unknown_champions: string[]
+}
+
+export interface ISimilarTeam {
+    champion_ids: string[]
+    // Jaccard similarity, from 0 to 1
+    similarity: number
+}
+
+export interface ITraitGraph {
+    champions: Array<{
+        id: string
+        name: string
+        component: number
+    }>
+    // Champion ids, with the ids of every trait they share
+    edges: Array<{
+        source: string
+        target: string
+        traits: string[]
+    }>
+    num_components: number
+}
+
+export interface IBackbone {
+    // Champion ids in every team matching the options
+    forced_in: string[]
+    // Champion ids in none of them
+    forced_out: string[]
+}
+
+export interface IPrunedChampion {
+    id: string
+    reason: 'outside_slots' | 'isolated' | 'component_too_small'
+}
+
+export type IConstraintFamily =
+    | 'decisions'
+    | 'connectivity'
+    | 'no_repeat'
+    | 'edges'
+    | 'slot_coverage'
+    | 'slot_dedupe'
+    | 'min_degree'
+    | 'custom'
+    | 'imported'
+
+export interface IConstraintStats {
+    variables: number
+    clauses: number
+    literals: number
+    families: Array<{
+        family: IConstraintFamily
+        constraints: number
+        clauses: number
+        literals: number
+    }>
+}
+
+export interface ISearchProgress {
+    teams_found: number
+    // Only counted by the cdcl backend
+    conflicts?: number
+    elapsed_ms: number
+    // Estimate from 0 to 1
+    fraction_searched: number
+}
+"#;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(typescript_type = "ISearchTeamsOptions")]
+    pub type ISearchTeamsOptions;
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use itertools::Itertools;
+
+    use crate::set_data::SetData;
+    use super::super::error::SearchError;
+    use crate::sat::{ ConstraintFamily, PruneReason };
+    use crate::team_mask::TeamMask;
+    use super::super::cursor::SearchCursor;
+    use super::{ init_search, Search, SearchOptions, TeamFinder };
+
+    fn set_data(num_champions: usize) -> SetData {
+        set_data_with_traits(&vec![None; num_champions])
+    }
+
+    /**
+     * Champion i is TFT_{i} with the trait traits[i], if any
+     */
+    fn set_data_with_traits(traits: &[Option<&str>]) -> SetData {
+        let (champions, traits) = set_data_json(traits);
+        SetData::from_json(&champions, &traits).unwrap()
+    }
+
+    /**
+     * Champions and traits json for set_data_with_traits()
+     */
+    fn set_data_json(traits: &[Option<&str>]) -> (String, String) {
+        let champions: Vec<String> = traits
+            .iter()
+            .enumerate()
+            .map(|(idx, trait_id)|
+                format!(
+                    r#"{{
+                        "character_id": "TFT_{}",
+                        "display_name": "{}",
+                        "tier": 1,
+                        "traits": [{}],
+                        "damage_type": {{ "is_ad": true, "is_ap": false }},
+                        "stats": {{}}
+                    }}"#,
+                    idx,
+                    idx,
+                    trait_id
+                        .map(|id| format!(r#"{{ "id": "{}", "name": "{}" }}"#, id, id))
+                        .unwrap_or_default()
+                )
+            )
+            .collect();
+
+        let trait_ids: HashSet<&str> = traits.iter().flatten().copied().collect();
+        let traits: Vec<String> = trait_ids
+            .iter()
+            .map(|id|
+                format!(
+                    r#"{{
+                        "trait_id": "{}",
+                        "display_name": "{}",
+                        "levels": [{{ "min_units": 2, "style_name": "kBronze" }}]
+                    }}"#,
+                    id,
+                    id
+                )
+            )
+            .collect();
+
+        (
+            format!("[{}]", champions.join(",")),
+            format!("[{}]", traits.join(","))
+        )
+    }
+
+    fn ids(indices: &[usize]) -> Vec<String> {
+        indices.iter().map(|idx| format!("TFT_{}", idx)).collect()
+    }
+
+    fn options(team_size: u8, slots: Vec<Vec<String>>) -> SearchOptions {
+        SearchOptions {
+            team_size,
+            slots,
+            diversity: None,
+            backend: None,
+            disabled_constraints: None,
+            max_clauses: None,
+            min_synergy: None,
+            debug: None,
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        let data = set_data(3);
+
+        assert_eq!(
+            options(2, vec![ids(&[0, 1]), ids(&[2])]).validate(&data),
+            Ok(())
+        );
+
+        assert_eq!(
+            options(4, vec![]).validate(&data),
+            Err(SearchError::TeamTooLarge {
+                team_size: 4,
+                num_champions: 3,
+            })
+        );
+
+        // One champion can't fill both slots
+        assert_eq!(
+            options(1, vec![ids(&[0, 1, 2]), ids(&[0])]).validate(&data),
+            Err(SearchError::TooManySlots {
+                team_size: 1,
+                num_slots: 2,
+            })
+        );
+
+        assert_eq!(
+            options(2, vec![ids(&[0]), vec![]]).validate(&data),
+            Err(SearchError::EmptySlot(1))
+        );
+
+        assert_eq!(
+            options(2, vec![ids(&[0, 3])]).validate(&data),
+            Err(SearchError::UnknownChampion("TFT_3".to_string()))
+        );
+
+        let mut unknown_backend = options(1, vec![]);
+        unknown_backend.backend = Some("glucose".to_string());
+        assert_eq!(
+            unknown_backend.validate(&data),
+            Err(SearchError::UnknownBackend("glucose".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_large_set() {
+        let data = set_data(300);
+
+        let slots = vec![ids(&[0, 299]), ids(&[256])];
+        assert_eq!(options(2, slots).validate(&data), Ok(()));
+        assert_eq!(data.index_of("TFT_299"), Some(299));
+    }
+
+    #[test]
+    fn test_fingerprint_ignores_order() {
+        let data = set_data(3);
+        let reordered = SetData::new(
+            data.champions().iter().rev().cloned().collect(),
+            data.traits.clone()
+        ).unwrap();
+
+        let options = options(2, vec![ids(&[0, 1]), ids(&[2])]);
+        assert_eq!(
+            options.fingerprint(&data),
+            options.fingerprint(&reordered)
+        );
+    }
+
+    #[test]
+    fn test_fingerprint_normalizes() {
+        let data = set_data(3);
+        let default = options(2, vec![]).fingerprint(&data);
+
+        for diversity in [Some(0), Some(1)] {
+            let mut options = options(2, vec![]);
+            options.diversity = diversity;
+            assert_eq!(options.fingerprint(&data), default);
+        }
+
+        let mut options = options(2, vec![]);
+        options.min_synergy = Some(0);
+        assert_eq!(options.fingerprint(&data), default);
+
+        options.min_synergy = Some(1);
+        assert_ne!(options.fingerprint(&data), default);
+    }
+
+    #[test]
+    fn test_load_set_data() {
+        let mut finder = TeamFinder::new();
+        let (champions, traits) = set_data_json(&[Some("A"); 6]);
+        finder.load_set_data(&champions, &traits).unwrap();
+
+        finder.search = init_search(&finder.set_data, &options(2, vec![]))
+            .unwrap();
+        for _ in 0..3 {
+            let team = finder.search.next().unwrap();
+            finder.history.push(TeamMask::from_iter(&team));
+        }
+
+        // Teams of the previous set would refer to champions that are gone
+        let (champions, traits) = set_data_json(&[Some("A"); 2]);
+        finder.load_set_data(&champions, &traits).unwrap();
+
+        assert!(finder.next().is_ok());
+        assert!(finder.search.components.is_empty());
+        assert!(finder.search_cursor().teams.is_empty());
+    }
+
+    #[test]
+    fn test_components() {
+        //   0 - 1 - 2    3 - 4    5
+        let data = set_data_with_traits(
+            &[
+                Some("A"),
+                Some("A"),
+                Some("A"),
+                Some("B"),
+                Some("B"),
+                None,
+            ]
+        );
+
+        let mut search = init_search(&data, &options(2, vec![])).unwrap();
+        assert_eq!(search.components.len(), 2);
+        assert_eq!(search.pruned.len(), 1);
+
+        // Components take turns
+        let first = search.next().unwrap();
+        let second = search.next().unwrap();
+        assert!(first.iter().all(|c| *c < 3));
+        assert!(second.iter().all(|c| *c >= 3));
+
+        let mut teams = vec![first, second];
+        while let Some(team) = search.next() {
+            teams.push(team);
+        }
+        teams.sort();
+
+        assert_eq!(
+            teams,
+            vec![vec![0, 1], vec![0, 2], vec![1, 2], vec![3, 4]]
+        );
+
+        let progress = search.progress();
+        assert_eq!(progress.teams_found, 4);
+        assert_eq!(progress.fraction_searched, 1.0);
+
+        // Slots outside a component rule it out entirely
+        let search = init_search(&data, &options(2, vec![ids(&[3])]))
+            .unwrap();
+        assert_eq!(search.components.len(), 1);
+        assert_eq!(search.components[0].champions, vec![3, 4]);
+    }
+
+    #[test]
+    fn test_restore() {
+        //   0 - 1 - 2    3 - 4
+        let data = set_data_with_traits(
+            &[Some("A"), Some("A"), Some("A"), Some("B"), Some("B")]
+        );
+        let options = options(2, vec![]);
+        let cursor = |teams: &[&[usize]]| SearchCursor {
+            options_hash: options.fingerprint(&data),
+            teams: Vec::from_iter(teams.iter().map(|team| ids(team))),
+        };
+
+        // Repeated teams are only restored once, and never found again
+        let mut search = init_search(&data, &options).unwrap();
+        let restored = search
+            .restore(&data, &options, cursor(&[&[0, 1], &[4, 3], &[0, 1]]))
+            .unwrap();
+        assert_eq!(
+            restored,
+            vec![TeamMask::from_iter(&[0, 1]), TeamMask::from_iter(&[3, 4])]
+        );
+
+        let mut teams = vec![];
+        while let Some(team) = search.next() {
+            teams.push(team);
+        }
+        teams.sort();
+        assert_eq!(teams, vec![vec![0, 2], vec![1, 2]]);
+
+        // Teams this search can't return
+        for team in [&[0, 1, 2][..], &[0], &[0, 0], &[2, 3]] {
+            let mut search = init_search(&data, &options).unwrap();
+            let result = search.restore(&data, &options, cursor(&[team]));
+            assert!(
+                matches!(result, Err(SearchError::MalformedCursor(_))),
+                "{:?}",
+                team
+            );
+        }
+    }
+
+    #[test]
+    fn test_banned_champions() {
+        // 4 shares a trait with everyone, but isn't allowed in any slot
+        let data = set_data_with_traits(&[Some("A"); 5]);
+        let slots = vec![ids(&[0, 1]), ids(&[1, 2]), ids(&[2, 3])];
+
+        let mut search = init_search(&data, &options(3, slots)).unwrap();
+        assert_eq!(search.pruned, vec![(4, PruneReason::OutsideSlots)]);
+        assert!(
+            search.components
+                .iter()
+                .all(|c| !c.champions.contains(&4))
+        );
+
+        let mut num_teams = 0;
+        while let Some(team) = search.next() {
+            assert!(!team.contains(&4));
+            num_teams += 1;
+        }
+        assert!(num_teams > 0);
+    }
+
+    #[test]
+    fn test_direct_enumeration() {
+        //   0 - 1 - 2    3 - 4
+        //       |
+        //       5
+        let data = set_data_with_traits(
+            &[
+                Some("A"),
+                Some("A"),
+                Some("A"),
+                Some("B"),
+                Some("B"),
+                Some("A"),
+            ]
+        );
+
+        let collect = |search: &mut Search| {
+            let mut teams = Vec::from_iter(std::iter::from_fn(|| search.next()));
+            teams.sort();
+            teams
+        };
+
+        // Connectivity alone doesn't need a formula
+        let mut direct = init_search(&data, &options(3, vec![])).unwrap();
+        assert_eq!(direct.stats.clauses, 0);
+
+        // Unless a backend is asked for
+        let mut sat_options = options(3, vec![]);
+        sat_options.backend = Some("minisat".to_string());
+        let mut sat = init_search(&data, &sat_options).unwrap();
+        assert!(sat.stats.clauses > 0);
+
+        let teams = collect(&mut direct);
+        assert_eq!(teams, collect(&mut sat));
+        assert_eq!(direct.progress().teams_found, 4);
+
+        // Every champion of A shares it with both others
+        let mut synergy_options = options(3, vec![]);
+        synergy_options.min_synergy = Some(2);
+        let mut synergy = init_search(&data, &synergy_options).unwrap();
+        assert!(
+            synergy.stats.families
+                .iter()
+                .any(|s| s.family == ConstraintFamily::MinDegree)
+        );
+        assert_eq!(collect(&mut synergy), teams);
+        assert_ne!(
+            synergy_options.fingerprint(&data),
+            options(3, vec![]).fingerprint(&data)
+        );
+    }
+
+    #[test]
+    fn test_backbone() {
+        // A: 0, 1, 2    B: 3, 4    5
+        let data = set_data_with_traits(
+            &[
+                Some("A"),
+                Some("A"),
+                Some("A"),
+                Some("B"),
+                Some("B"),
+                None,
+            ]
+        );
+
+        // Teams in both components, so nobody is forced in
+        let search = init_search(&data, &options(2, vec![])).unwrap();
+        assert_eq!(search.backbone(6), (vec![], vec![5]));
+
+        // Only A has a team of 3
+        let mut search = init_search(&data, &options(3, vec![])).unwrap();
+        assert_eq!(search.backbone(6), (vec![0, 1, 2], vec![3, 4, 5]));
+
+        // Already returned teams still count
+        search.next().unwrap();
+        assert_eq!(search.backbone(6), (vec![0, 1, 2], vec![3, 4, 5]));
+
+        // Same answer from the SAT solver
+        let mut sat_options = options(3, vec![]);
+        sat_options.backend = Some("cdcl".to_string());
+        let search = init_search(&data, &sat_options).unwrap();
+        assert_eq!(search.backbone(6), (vec![0, 1, 2], vec![3, 4, 5]));
+
+        // Slots are taken into account
+        let search = init_search(&data, &options(2, vec![ids(&[0])]))
+            .unwrap();
+        assert_eq!(search.backbone(6), (vec![0], vec![3, 4, 5]));
+
+        // No teams at all
+        let mut synergy_options = options(2, vec![]);
+        synergy_options.min_synergy = Some(2);
+        let search = init_search(&data, &synergy_options).unwrap();
+        assert_eq!(search.backbone(6), (vec![], Vec::from_iter(0..6)));
+    }
+
+    #[test]
+    fn test_disabled_constraints() {
+        let data = set_data_with_traits(
+            &[Some("A"), Some("A"), Some("B"), Some("B"), None]
+        );
+
+        // Without connectivity any pair of champions is a team
+        let mut options = options(2, vec![]);
+        options.disabled_constraints = Some(
+            vec![ConstraintFamily::Connectivity]
+        );
+
+        let mut search = init_search(&data, &options).unwrap();
+        assert!(search.pruned.is_empty());
+        assert!(
+            search.stats.families
+                .iter()
+                .all(|s| s.family != ConstraintFamily::Connectivity)
+        );
+
+        let mut teams = vec![];
+        while let Some(team) = search.next() {
+            teams.push(team);
+        }
+        assert_eq!(teams.len(), 10);
+
+        // Disabling constraints changes which teams are found
+        let hash = options.fingerprint(&data);
+        options.disabled_constraints = None;
+        assert_ne!(options.fingerprint(&data), hash);
+    }
+
+    /**
+     * Whether each slot can be filled by a different member of the team
+     */
+    fn fills_slots(team: &[usize], slots: &[Vec<String>]) -> bool {
+        fn assign(
+            team: &[usize],
+            slots: &[Vec<String>],
+            used: &mut Vec<usize>
+        ) -> bool {
+            let Some((slot, rest)) = slots.split_first() else {
+                return true;
+            };
+
+            for champion in team.iter() {
+                let id = format!("TFT_{}", champion);
+                if used.contains(champion) || !slot.contains(&id) {
+                    continue;
+                }
+
+                used.push(*champion);
+                if assign(team, rest, used) {
+                    return true;
+                }
+                used.pop();
+            }
+
+            false
+        }
+
+        assign(team, slots, &mut vec![])
+    }
+
+    #[test]
+    fn test_slots() {
+        let data = set_data_with_traits(&[Some("A"); 4]);
+
+        // Champion 0 can't fill both slots
+        let slots = vec![ids(&[0, 1]), ids(&[0, 2])];
+        let mut search = init_search(&data, &options(2, slots.clone()))
+            .unwrap();
+
+        let mut teams = vec![];
+        while let Some(team) = search.next() {
+            assert!(fills_slots(&team, &slots));
+            teams.push(team);
+        }
+        teams.sort();
+
+        assert_eq!(teams, vec![vec![0, 1], vec![0, 2], vec![1, 2]]);
+    }
+
+    #[test]
+    fn test_overlapping_slots() {
+        let data = set_data_with_traits(&[Some("A"); 12]);
+
+        // 4 slots of 6 options, each sharing 4 with the next
+        let slots = Vec::from_iter(
+            (0..4).map(|s| ids(&Vec::from_iter(s * 2..s * 2 + 6)))
+        );
+
+        let mut search = init_search(&data, &options(4, slots.clone()))
+            .unwrap();
+
+        let mut actual = vec![];
+        while let Some(team) = search.next() {
+            actual.push(team);
+        }
+        actual.sort();
+
+        let expected = Vec::from_iter(
+            (0..12)
+                .combinations(4)
+                .filter(|team| fills_slots(team, &slots))
+        );
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_nine_overlapping_slots() {
+        let data = set_data_with_traits(&[Some("A"); 12]);
+
+        // 9 slots of 7 options, each sharing at least 6 with the next
+        let slots = Vec::from_iter(
+            (0..9).map(|s| ids(&Vec::from_iter(s / 2..s / 2 + 7)))
+        );
+
+        let mut search = init_search(&data, &options(9, slots.clone()))
+            .unwrap();
+
+        // Linear in the number of options (63),
+        // rather than exponential in the overlap
+        let slot_clauses: usize = search.stats.families
+            .iter()
+            .filter(|s|
+                s.family == ConstraintFamily::SlotCoverage ||
+                s.family == ConstraintFamily::SlotDedupe
+            )
+            .map(|s| s.clauses)
+            .sum();
+        assert!(slot_clauses < 63 * 10, "{} clauses", slot_clauses);
+
+        let mut actual = vec![];
+        while let Some(team) = search.next() {
+            actual.push(team);
+        }
+        actual.sort();
+
+        let expected = Vec::from_iter(
+            (0..12)
+                .combinations(9)
+                .filter(|team| fills_slots(team, &slots))
+        );
+        // 55 of the 220 teams of 9 fill the slots
+        assert_eq!(expected.len(), 55);
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_max_clauses() {
+        let data = set_data_with_traits(&[Some("A"); 20]);
+        let slots = vec![ids(&[0, 1, 2]), ids(&[1, 2, 3])];
+
+        let mut expected = vec![];
+        let mut search = init_search(&data, &options(3, slots.clone()))
+            .unwrap();
+        while let Some(team) = search.next() {
+            expected.push(team);
+        }
+        expected.sort();
+
+        // Too large for the dense encoding, which has a clause per pair of vertices
+        let mut sparse = options(3, slots.clone());
+        sparse.max_clauses = Some(1_000);
+
+        let mut search = init_search(&data, &sparse).unwrap();
+        assert!(search.stats.clauses <= 1_000);
+        assert!(
+            search.stats.families
+                .iter()
+                .all(|s| s.family != ConstraintFamily::Edges)
+        );
+
+        let mut actual = vec![];
+        while let Some(team) = search.next() {
+            actual.push(team);
+        }
+        actual.sort();
+        assert_eq!(actual, expected);
+
+        let mut too_large = options(3, slots);
+        too_large.max_clauses = Some(10);
+        assert!(
+            matches!(
+                init_search(&data, &too_large),
+                Err(SearchError::SearchTooLarge { max_clauses: 10, .. })
+            )
+        );
+    }
+}
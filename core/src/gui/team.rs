@@ -0,0 +1,89 @@
+use serde::{ Deserialize, Serialize };
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::sat::Solution;
+use crate::set_data::SetData;
+use crate::trait_hypergraph::TraitHypergraph;
+use super::error::SearchError;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Team {
+    // eg TFT11_Ahri
+    pub champion_ids: Vec<String>,
+}
+
+/**
+ * Members of a team that share a trait,
+ * so a team can be explained by the traits that connect it
+ */
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TeamTrait {
+    // eg TFT11_Fated
+    pub id: String,
+    pub champion_ids: Vec<String>,
+}
+
+impl Team {
+    pub fn new(
+        vertices: Solution,
+        set_data: &SetData
+    ) -> Result<Self, SearchError> {
+        let champion_ids = vertices
+            .iter()
+            .map(|idx|
+                set_data.champions()
+                    .get(*idx)
+                    .map(|champion| champion.id.clone())
+                    .ok_or_else(||
+                        SearchError::Internal(
+                            format!("Solution has unknown vertex {}", idx)
+                        )
+                    )
+            )
+            .collect::<Result<Vec<String>, SearchError>>()?;
+
+        Ok(Self {
+            champion_ids,
+        })
+    }
+}
+
+impl TeamTrait {
+    /**
+     * Every trait of a team (champion indices), sorted by id
+     */
+    pub fn of_team(
+        team: &[usize],
+        set_data: &SetData,
+        traits: &TraitHypergraph
+    ) -> Vec<Self> {
+        Vec::from_iter(
+            traits
+                .team_traits(team)
+                .into_iter()
+                .map(|(t, members)| TeamTrait {
+                    id: t.id.clone(),
+                    champion_ids: Vec::from_iter(
+                        members
+                            .iter()
+                            .map(|idx| set_data.champions()[*idx].id.clone())
+                    ),
+                })
+        )
+    }
+}
+
+#[wasm_bindgen(typescript_custom_section)]
+const TYPES: &'static str =
+    r#"
+
+interface Team {
+    champion_ids: string[]
+}
+
+export interface ITeamTrait {
+    id: string
+    champion_ids: string[]
+}
+
+"#;
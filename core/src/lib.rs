@@ -0,0 +1,8 @@
+mod console;
+
+pub mod sat;
+pub mod gui;
+pub mod set_data;
+pub mod team_mask;
+pub mod trait_hypergraph;
+pub mod trait_graph;
@@ -1,2 +0,0 @@
-mod search_teams;
-mod team;
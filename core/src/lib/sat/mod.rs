@@ -1,9 +0,0 @@
-mod utils;
-mod build_subgraph_constraints;
-mod solve;
-mod build_champion_constraints;
-
-pub use build_subgraph_constraints::*;
-pub use solve::*;
-pub use utils::*;
-pub use build_champion_constraints::*;
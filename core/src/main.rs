@@ -1,33 +1,182 @@
-mod console;
-mod lib;
+use tft_core::set_data::SetData;
+use tft_core::trait_graph::TraitGraph;
+use tft_core::sat::{
+    build_kite_graph,
+    read_dimacs,
+    CdclBackend,
+    MiniSatBackend,
+    SubgraphSearch,
+    SubgraphSolver,
+    TeamSolverBackend,
+};
 
-use console::log;
+const USAGE: &str = "\
+Usage:
+  tft-core [search.cnf] [--backend=minisat|cdcl] [--partitions=N]
+      Enumerates the solutions of a DIMACS file exported by TeamFinder.dimacs()
+      (or a small example graph without one)
+  tft-core --trait-graph=dot|json <champions.json> <traits.json>
+      Prints the trait graph of a set";
 
-use crate::lib::sat::{ build_kite_graph, SubgraphSolver };
+#[derive(Clone, Copy, PartialEq)]
+enum TraitGraphFormat {
+    Dot,
+    Json,
+}
+
+/**
+ * Bad arguments are reported like this rather than with a panic
+ */
+fn exit_with_usage(message: &str) -> ! {
+    eprintln!("{}\n\n{}", message, USAGE);
+    std::process::exit(2)
+}
+
+fn read_file(path: &str) -> String {
+    std::fs::read_to_string(path).unwrap_or_else(|e|
+        exit_with_usage(&format!("Can't read {}: {}", path, e))
+    )
+}
 
 fn main() {
-    let constraints = build_kite_graph(5);
-
-    log!(
-        "Solving with {} constraints mapped to {} clauses",
-        constraints.num_constraints,
-        constraints.factory
-            .cnf_of(constraints.formula)
-            .to_string(&constraints.factory)
-            .chars()
-            .filter(|c| *c == '&')
-            .count() + 1
+    let (flags, paths): (Vec<String>, Vec<String>) = std::env::args()
+        .skip(1)
+        .partition(|arg| arg.starts_with("--"));
+
+    let mut trait_graph = None;
+    let mut use_cdcl = false;
+    let mut num_partitions = None;
+
+    for flag in flags.iter() {
+        let (name, value) = flag.split_once('=').unwrap_or((flag, ""));
+
+        match (name, value) {
+            ("--trait-graph", "dot") => {
+                trait_graph = Some(TraitGraphFormat::Dot)
+            }
+            ("--trait-graph", "json") => {
+                trait_graph = Some(TraitGraphFormat::Json)
+            }
+            ("--backend", "minisat") => use_cdcl = false,
+            ("--backend", "cdcl") => use_cdcl = true,
+            ("--partitions", n) => {
+                num_partitions = match n.parse::<usize>() {
+                    Ok(n) if n > 0 => Some(n),
+                    _ => exit_with_usage(&format!(
+                        "Expected a positive number of partitions, got {:?}",
+                        n
+                    )),
+                };
+            }
+            ("--trait-graph" | "--backend", value) => {
+                exit_with_usage(
+                    &format!("Unknown value {:?} for {}", value, name)
+                )
+            }
+            _ => exit_with_usage(&format!("Unknown flag {}", flag)),
+        }
+    }
+
+    // Print the trait graph of a set instead of solving anything
+    //   cargo run -- --trait-graph=dot merged_teamplanner_data.json traits.json
+    if let Some(format) = trait_graph {
+        let [champions_path, traits_path] = paths.as_slice() else {
+            exit_with_usage(
+                "Expected the paths to the champions and traits json"
+            );
+        };
+
+        let set_data = SetData::from_json(
+            &read_file(champions_path),
+            &read_file(traits_path)
+        ).unwrap_or_else(|e| exit_with_usage(&e.to_string()));
+        let graph = TraitGraph::new(&set_data);
+
+        match format {
+            TraitGraphFormat::Dot => print!("{}", graph.to_dot()),
+            TraitGraphFormat::Json => {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&graph)
+                        .expect("trait graphs only contain strings and numbers")
+                )
+            }
+        }
+        return;
+    }
+
+    if paths.len() > 1 {
+        exit_with_usage("Expected at most one DIMACS file");
+    }
+
+    // Optionally solve a DIMACS file exported by TeamFinder.dimacs()
+    //   cargo run -- search.cnf --backend=cdcl
+    let input = paths.first().map(|path| read_file(path));
+    let build_constraints = || match &input {
+        Some(input) => read_dimacs(input).unwrap_or_else(|e|
+            exit_with_usage(&format!("Invalid DIMACS file: {}", e))
+        ),
+        None => build_kite_graph(5),
+    };
+
+    let build_backend = || -> Box<dyn TeamSolverBackend> {
+        if use_cdcl {
+            Box::new(CdclBackend::new())
+        } else {
+            Box::new(MiniSatBackend::new())
+        }
+    };
+
+    let constraints = build_constraints();
+
+    let stats = constraints.stats();
+    eprintln!(
+        "Solving with {} constraints mapped to {} clauses over {} variables",
+        constraints.num_constraints(),
+        stats.clauses,
+        stats.variables
     );
 
-    let mut solver = SubgraphSolver::new(constraints);
+    // Split the search across threads
+    //   cargo run -- search.cnf --partitions=16
+    #[cfg(not(target_arch = "wasm32"))]
+    if let Some(num_partitions) = num_partitions {
+        let start = web_time::Instant::now();
+        let solutions = tft_core::sat::enumerate_parallel(
+            || SubgraphSolver::new_with_backend(
+                build_constraints(),
+                build_backend()
+            ),
+            num_partitions
+        );
+
+        eprintln!(
+            "Found {} solutions in {} partitions. [{}ms]",
+            solutions.len(),
+            num_partitions,
+            start.elapsed().as_millis()
+        );
+        return;
+    }
+
+    let backend = build_backend();
+
+    let start = web_time::Instant::now();
+    let mut solver = SubgraphSolver::new_with_backend(
+        constraints,
+        backend
+    );
 
     loop {
         match solver.next() {
             Some(model) => {
-                log!("Found solution: {:?}", model);
+                println!("Found solution: {:?}", model);
             }
             None => {
-                log!("No more solutions.");
+                eprintln!(
+                    "No more solutions. [{}ms]",
+                    start.elapsed().as_millis()
+                );
                 break;
             }
         }
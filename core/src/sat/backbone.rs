@@ -0,0 +1,167 @@
+use logicng::formulas::Literal;
+
+use super::{ SubgraphConstraints, TeamSolverBackend, TeamVariable };
+
+/**
+ * Vertices that are in every solution, or in none
+ */
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Backbone {
+    // Ascending
+    pub forced_in: Vec<usize>,
+    pub forced_out: Vec<usize>,
+}
+
+/**
+ * Backbone of the formula over the vertex variables,
+ * or None if it has no solutions
+ *
+ * The backend should be empty, so solutions that were already blocked
+ * elsewhere still count.
+ * Each vertex that has the same value in every model found so far
+ * is checked by assuming the opposite value, and every model found
+ * along the way rules out the vertices it disagrees on.
+ */
+pub fn vertex_backbone(
+    constraints: &SubgraphConstraints,
+    mut backend: Box<dyn TeamSolverBackend>
+) -> Option<Backbone> {
+    constraints.add_to(backend.as_mut());
+
+    let vertices = constraints.variables.vertices();
+    if !backend.solve(&[]) {
+        return None;
+    }
+
+    let phases = |model: Vec<Literal>| {
+        let mut phases = vec![false; vertices.len()];
+        for lit in model.iter() {
+            let var = constraints.variables.decode(lit.variable());
+            if let Some(TeamVariable::Vertex(idx)) = var {
+                phases[idx] = lit.phase();
+            }
+        }
+
+        phases
+    };
+
+    let first = phases(backend.model(vertices)?);
+    let mut is_candidate = vec![true; vertices.len()];
+    let mut backbone = Backbone::default();
+
+    for idx in 0..vertices.len() {
+        if !is_candidate[idx] {
+            continue;
+        }
+
+        let flipped = Literal::new(vertices[idx], !first[idx]);
+        if !backend.solve(&[flipped]) {
+            if first[idx] {
+                backbone.forced_in.push(idx);
+            } else {
+                backbone.forced_out.push(idx);
+            }
+            continue;
+        }
+
+        let model = phases(backend.model(vertices)?);
+        for (other, phase) in model.iter().enumerate() {
+            if *phase != first[other] {
+                is_candidate[other] = false;
+            }
+        }
+    }
+
+    Some(backbone)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sat::{
+        build_kite_graph,
+        build_square_graph,
+        Constraint,
+        CdclBackend,
+        ConstraintFamily,
+        MiniSatBackend,
+        SubgraphConstraints,
+    };
+
+    use super::{ vertex_backbone, Backbone };
+
+    /**
+     * Backbone with MiniSat, after checking the CDCL backend agrees
+     */
+    fn backbone(constraints: &SubgraphConstraints) -> Option<Backbone> {
+        let expected = vertex_backbone(
+            constraints,
+            Box::new(MiniSatBackend::new())
+        );
+        let actual = vertex_backbone(
+            constraints,
+            Box::new(CdclBackend::new())
+        );
+        assert_eq!(actual, expected);
+
+        expected
+    }
+
+    #[test]
+    fn test_kite() {
+        // d is the only way to reach e
+        assert_eq!(
+            backbone(&build_kite_graph(4)),
+            Some(Backbone {
+                forced_in: vec![3],
+                forced_out: vec![],
+            })
+        );
+
+        assert_eq!(
+            backbone(&build_kite_graph(5)),
+            Some(Backbone {
+                forced_in: vec![0, 1, 2, 3, 4],
+                forced_out: vec![],
+            })
+        );
+
+        assert_eq!(
+            backbone(&build_kite_graph(2)),
+            Some(Backbone::default())
+        );
+    }
+
+    #[test]
+    fn test_unsatisfiable() {
+        let mut constraints = build_square_graph(3);
+        let unit = Constraint::Clause(
+            vec![constraints.variables.vertex(0).neg_lit()]
+        );
+        let unit_2 = Constraint::Clause(
+            vec![constraints.variables.vertex(3).neg_lit()]
+        );
+        constraints.extend(
+            vec![
+                (ConstraintFamily::Imported, unit),
+                (ConstraintFamily::Imported, unit_2)
+            ]
+        );
+        assert_eq!(backbone(&constraints), None);
+
+        // Without a, only b - d - c is left
+        let mut constraints = build_square_graph(3);
+        let unit = Constraint::Clause(
+            vec![constraints.variables.vertex(0).neg_lit()]
+        );
+        constraints.extend(
+            vec![(ConstraintFamily::Imported, unit)]
+        );
+        assert_eq!(
+            backbone(&constraints),
+            Some(Backbone {
+                forced_in: vec![1, 2, 3],
+                forced_out: vec![0],
+            })
+        );
+    }
+}
@@ -0,0 +1,182 @@
+use logicng::{
+    formulas::{ EncodedFormula, FormulaFactory, Literal, Variable },
+    solver::minisat::{
+        sat::{ mk_lit, MsLit, MsVar, Tristate },
+        MiniSat,
+        SatBuilder,
+    },
+};
+
+use super::cnf_clauses;
+
+/**
+ * Incremental SAT solver used by SubgraphSolver
+ *
+ * Constraints are still built as LogicNG formulas,
+ * backends only need to deal with (CNF) clauses over LogicNG variables
+ */
+pub trait TeamSolverBackend {
+    /**
+     * A backend of the same kind without any clauses,
+     * for queries that shouldn't see blocked models (eg the backbone)
+     */
+    fn fresh(&self) -> Box<dyn TeamSolverBackend>;
+
+    fn add_clause(
+        &mut self,
+        clause: &[Literal],
+        f: &FormulaFactory
+    );
+
+    /**
+     * Returns true if the clauses (plus the assumptions) are satisfiable
+     * The assumptions only apply to this call
+     */
+    fn solve(&mut self, assumptions: &[Literal]) -> bool;
+
+    /**
+     * Assignment of the variables from the last successful solve()
+     * Variables the solver has never seen are omitted
+     */
+    fn model(&self, variables: &[Variable]) -> Option<Vec<Literal>>;
+
+    /**
+     * Adds a clause that forbids the last model's assignment of these variables
+     */
+    fn block_model(
+        &mut self,
+        variables: &[Variable],
+        f: &FormulaFactory
+    );
+
+    /**
+     * Conflicts encountered so far, if the backend keeps count
+     * (LogicNG's MiniSat doesn't expose it)
+     */
+    fn num_conflicts(&self) -> Option<u64> {
+        None
+    }
+
+    fn add_formula(
+        &mut self,
+        formula: EncodedFormula,
+        f: &FormulaFactory
+    ) {
+        for clause in cnf_clauses(f.cnf_of(formula), f) {
+            self.add_clause(&clause, f);
+        }
+    }
+}
+
+pub struct MiniSatBackend {
+    pub solver: MiniSat,
+}
+
+impl Default for MiniSatBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MiniSatBackend {
+    pub fn new() -> Self {
+        Self {
+            solver: MiniSat::new(),
+        }
+    }
+}
+
+impl MiniSatBackend {
+    /**
+     * Same as MiniSat's own mapping, which isn't public
+     */
+    fn ms_var(&mut self, var: Variable) -> MsVar {
+        let solver = &mut self.solver.underlying_solver;
+
+        solver.idx_for_variable(var).unwrap_or_else(|| {
+            // Negative initial phase, like MiniSatConfig's default
+            let idx = solver.new_var(true, true);
+            solver.add_variable(var, idx);
+            idx
+        })
+    }
+}
+
+impl TeamSolverBackend for MiniSatBackend {
+    fn fresh(&self) -> Box<dyn TeamSolverBackend> {
+        Box::new(Self::new())
+    }
+
+    // Straight onto the underlying solver, since going through a clause formula
+    // would cache its NNF, literals and variables in the factory
+    fn add_clause(
+        &mut self,
+        clause: &[Literal],
+        _f: &FormulaFactory
+    ) {
+        let lits = Vec::from_iter(
+            clause
+                .iter()
+                .map(|lit| mk_lit(self.ms_var(lit.variable()), !lit.phase()))
+        );
+
+        self.solver.underlying_solver.add_clause(lits, &None);
+    }
+
+    // Cardinality constraints are encoded directly onto the solver
+    // so there's no need to go through the factory's CNF conversion
+    fn add_formula(
+        &mut self,
+        formula: EncodedFormula,
+        f: &FormulaFactory
+    ) {
+        self.solver.add(formula, f);
+    }
+
+    fn solve(&mut self, assumptions: &[Literal]) -> bool {
+        let result = if assumptions.is_empty() {
+            self.solver.sat()
+        } else {
+            self.solver.sat_with(
+                &SatBuilder::new().assumptions(assumptions)
+            )
+        };
+
+        result == Tristate::True
+    }
+
+    fn model(&self, variables: &[Variable]) -> Option<Vec<Literal>> {
+        self.solver.model(Some(variables)).map(|model| model.literals())
+    }
+
+    // https://github.com/booleworks/logicng-rs/blob/2fc0f76558fb9194cdf8a44b4c67a243116ea61c/src/solver/functions/model_enumeration.rs#L268
+    fn block_model(
+        &mut self,
+        variables: &[Variable],
+        _f: &FormulaFactory
+    ) {
+        let relevant_indices: Vec<MsVar> = variables
+            .iter()
+            .filter_map(|&v|
+                self.solver.underlying_solver.idx_for_variable(v)
+            )
+            .collect();
+
+        let model_from_solver = &self.solver.underlying_solver.model;
+
+        let mut blocking_clause = Vec::<MsLit>::with_capacity(
+            relevant_indices.len()
+        );
+
+        for var_index in relevant_indices {
+            blocking_clause.push(
+                mk_lit(var_index, model_from_solver[var_index.0])
+            );
+        }
+
+        self.solver.underlying_solver.add_clause(
+            blocking_clause,
+            &None
+        );
+    }
+}
@@ -0,0 +1,265 @@
+use std::{ collections::HashSet };
+
+use crate::set_data::SetData;
+use crate::trait_hypergraph::TraitHypergraph;
+
+use super::{
+    prune_graph,
+    ConnectedSubgraphProblem,
+    ConnectivityEncoding,
+    ConstraintFamily,
+    PruneReason,
+    SearchTooLarge,
+    SizeEstimate,
+    SubgraphConstraints,
+};
+
+/**
+ * Constraints for each connected component of the trait graph
+ * that could contain a team
+ *
+ * Teams can't span components, so each one can be solved independently
+ */
+pub struct ChampionConstraints {
+    pub components: Vec<ComponentConstraints>,
+    pub pruned: Vec<(usize, PruneReason)>,
+    // Same for every component
+    pub encoding: ConnectivityEncoding,
+    // Preflight estimate the encoding was chosen by
+    pub estimate: SizeEstimate,
+}
+
+/**
+ * Vertex i of the constraints is champion champions[i] of the set data
+ */
+pub struct ComponentConstraints {
+    pub constraints: SubgraphConstraints,
+    pub champions: Vec<usize>,
+}
+
+/**
+ * Connected components of the trait graph that could contain a team
+ */
+pub struct ChampionGraphs {
+    pub components: Vec<ComponentGraph>,
+    pub pruned: Vec<(usize, PruneReason)>,
+}
+
+/**
+ * A component of the trait graph, with its champions renumbered from 0
+ * (vertex i is champion champions[i] of the set data)
+ */
+pub struct ComponentGraph {
+    pub champions: Vec<usize>,
+    pub edges: HashSet<(i32, i32)>,
+    pub slot_options: Vec<Vec<usize>>,
+}
+
+/**
+ * Without the connectivity or edge constraints in `disabled`,
+ * the whole set is one complete graph.
+ * With a slot per team member, champions outside every slot are
+ * pruned here and never reach a component
+ */
+pub fn build_champion_graphs(
+    set_data: &SetData,
+    subgraph_size: u8,
+    slot_options: &[Vec<usize>],
+    disabled: &[ConstraintFamily]
+) -> ChampionGraphs {
+    let traits = TraitHypergraph::from_set_data(set_data);
+
+    // Without connectivity any champions can form a team,
+    // which is the same as every pair of champions sharing an edge
+    let ignores_graph =
+        disabled.contains(&ConstraintFamily::Connectivity) ||
+        disabled.contains(&ConstraintFamily::Edges);
+
+    let edges = if ignores_graph {
+        let num_champions = set_data.champions().len() as i32;
+
+        HashSet::<(i32, i32)>::from_iter(
+            (0..num_champions).flat_map(|a|
+                (a + 1..num_champions).map(move |b| (a, b))
+            )
+        )
+    } else {
+        // Champions that share traits
+        traits.edges()
+    };
+
+    let report = prune_graph(
+        set_data.champions().len(),
+        &edges,
+        subgraph_size as usize,
+        slot_options
+    );
+
+    let components = Vec::from_iter(
+        report.components
+            .into_iter()
+            .filter_map(|champions|
+                component_graph(champions, &edges, slot_options)
+            )
+    );
+
+    ChampionGraphs {
+        components,
+        pruned: report.removed,
+    }
+}
+
+/**
+ * Each champion of a team must share a trait with at least `min_degree` others
+ * (0 for no minimum beyond connectivity)
+ *
+ * Constraint families in `disabled` are left out of the formula
+ *
+ * Before building anything, the size of the formula is estimated.
+ * If the dense encoding would have more than `max_clauses` clauses,
+ * the sparse one is used instead, and if that's still too large
+ * nothing is built.
+ */
+pub fn build_champion_constraints(
+    set_data: &SetData,
+    subgraph_size: u8,
+    slot_options: &[Vec<usize>],
+    min_degree: usize,
+    disabled: &[ConstraintFamily],
+    max_clauses: Option<usize>
+) -> Result<ChampionConstraints, SearchTooLarge> {
+    let ChampionGraphs { components: graphs, pruned } = build_champion_graphs(
+        set_data,
+        subgraph_size,
+        slot_options,
+        disabled
+    );
+
+    // --- Preflight ---
+
+    let mut problems = Vec::from_iter(
+        graphs
+            .iter()
+            .map(|graph| graph.problem(subgraph_size, min_degree, disabled))
+    );
+
+    let estimate_all = |problems: &[ConnectedSubgraphProblem<usize>]| {
+        problems
+            .iter()
+            .map(|problem| problem.estimate(subgraph_size as usize))
+            .fold(SizeEstimate::default(), |total, e| total + e)
+    };
+
+    let max_clauses = max_clauses.unwrap_or(usize::MAX);
+    let mut encoding = ConnectivityEncoding::Dense;
+    let mut estimate = estimate_all(&problems);
+
+    if estimate.clauses > max_clauses {
+        for problem in problems.iter_mut() {
+            problem.encoding = ConnectivityEncoding::Sparse;
+        }
+
+        let sparse = estimate_all(&problems);
+        if sparse.clauses > max_clauses {
+            return Err(SearchTooLarge {
+                estimate: sparse,
+                max_clauses,
+            });
+        }
+
+        encoding = ConnectivityEncoding::Sparse;
+        estimate = sparse;
+    }
+
+    let components = Vec::from_iter(
+        problems
+            .into_iter()
+            .zip(graphs)
+            .map(|(problem, graph)| ComponentConstraints {
+                constraints: problem.build(subgraph_size as usize),
+                champions: graph.champions,
+            })
+    );
+
+    Ok(ChampionConstraints {
+        components,
+        pruned,
+        encoding,
+        estimate,
+    })
+}
+
+impl ComponentGraph {
+    /**
+     * Teams of this component as a generic problem over its vertices,
+     * so vertex i of its constraints is still champion champions[i]
+     */
+    pub fn problem(
+        &self,
+        subgraph_size: u8,
+        min_degree: usize,
+        disabled: &[ConstraintFamily]
+    ) -> ConnectedSubgraphProblem<usize> {
+        let size = subgraph_size as usize;
+
+        let mut problem = ConnectedSubgraphProblem::new(
+            0..self.champions.len(),
+            self.edges.iter().map(|(a, b)| (*a as usize, *b as usize)),
+            size..=size
+        ).expect("edges of a component are between its own vertices");
+
+        problem.min_degree = min_degree;
+        problem.disabled = disabled.to_vec();
+
+        for options in self.slot_options.iter() {
+            problem
+                .add_slot(options)
+                .expect("slot options of a component are its own vertices");
+        }
+
+        problem
+    }
+}
+
+/**
+ * Returns None if the component can't fill every slot
+ */
+fn component_graph(
+    champions: Vec<usize>,
+    edges: &HashSet<(i32, i32)>,
+    slot_options: &[Vec<usize>]
+) -> Option<ComponentGraph> {
+    // Renumber the component's champions from 0
+    let vertex_of = |champion: usize| champions.binary_search(&champion).ok();
+
+    let slot_options = Vec::from_iter(
+        slot_options
+            .iter()
+            .map(|slot|
+                Vec::from_iter(slot.iter().filter_map(|c| vertex_of(*c)))
+            )
+    );
+
+    // The whole team is inside this component,
+    // so every slot needs to be filled from it
+    if slot_options.iter().any(|slot| slot.is_empty()) {
+        return None;
+    }
+
+    let edges = HashSet::<(i32, i32)>::from_iter(
+        edges
+            .iter()
+            .filter_map(|(a, b)|
+                Some((
+                    vertex_of(*a as usize)? as i32,
+                    vertex_of(*b as usize)? as i32,
+                ))
+            )
+    );
+
+    Some(ComponentGraph {
+        champions,
+        edges,
+        slot_options,
+    })
+}
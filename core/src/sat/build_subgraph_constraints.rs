@@ -0,0 +1,555 @@
+use std::{ collections::HashSet };
+
+use logicng::formulas::{
+    CType,
+    EncodedFormula,
+    FormulaFactory,
+    Literal,
+};
+
+use super::{
+    cnf_clauses,
+    ConstraintFamily,
+    ConstraintStats,
+    TeamSolverBackend,
+    VariableRegistry,
+};
+
+/**
+ * A clause, or a cardinality constraint the backend encodes itself
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Constraint {
+    Clause(Vec<Literal>),
+    Formula(EncodedFormula),
+}
+
+/**
+ * Every constraint is a clause or a cardinality constraint,
+ * with auxiliary variables allocated explicitly by the builder,
+ * so they can be handed to the solver one at a time
+ * instead of converting one big conjunction to CNF
+ *
+ * Clauses are plain literals rather than formulas,
+ * so the factory only holds the variables and cardinality constraints.
+ * They're still kept after solving starts, for the stats,
+ * DIMACS export and backbone.
+ */
+pub struct SubgraphConstraints {
+    pub factory: FormulaFactory,
+    pub variables: VariableRegistry,
+    // Tagged with the part of the encoding they belong to
+    pub constraints: Vec<(ConstraintFamily, Constraint)>,
+    pub num_vertices: i32,
+    pub subgraph_size: i32,
+}
+
+impl SubgraphConstraints {
+    pub fn num_constraints(&self) -> usize {
+        self.constraints.len()
+    }
+
+    pub fn stats(&self) -> ConstraintStats {
+        ConstraintStats::of(&self.constraints, &self.factory)
+    }
+
+    pub fn extend(
+        &mut self,
+        constraints: Vec<(ConstraintFamily, Constraint)>
+    ) {
+        self.constraints.extend(constraints);
+    }
+
+    /**
+     * Drops every constraint of these families
+     */
+    pub fn disable(&mut self, families: &[ConstraintFamily]) {
+        self.constraints.retain(|(family, _)| !families.contains(family));
+    }
+
+    pub fn add_to(&self, backend: &mut dyn TeamSolverBackend) {
+        let f = &self.factory;
+
+        for (_, constraint) in self.constraints.iter() {
+            match constraint {
+                Constraint::Clause(clause) => backend.add_clause(clause, f),
+                Constraint::Formula(formula) => {
+                    backend.add_formula(*formula, f)
+                }
+            }
+        }
+    }
+
+    /**
+     * Only the cardinality constraints need to be converted
+     */
+    pub fn clauses(&self) -> Vec<Vec<Literal>> {
+        let f = &self.factory;
+
+        self.constraints
+            .iter()
+            .flat_map(|(_, constraint)| match constraint {
+                Constraint::Clause(clause) => vec![clause.clone()],
+                Constraint::Formula(formula) => {
+                    cnf_clauses(f.cnf_of(*formula), f)
+                }
+            })
+            .collect()
+    }
+}
+
+/**
+ * How "each picked vertex shares an edge with an earlier one" is encoded
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConnectivityEncoding {
+    // Edges are variables fixed by unit clauses,
+    // so the formula has the same shape for every graph of this size
+    // (and the graph can be read back from an exported DIMACS file)
+    Dense,
+    // Edges are inlined, so each vertex only refers to its neighbours.
+    // Much smaller for sparse graphs, but the graph is baked into the clauses
+    Sparse,
+}
+
+pub fn build_subgraph_contraints(
+    num_vertices: i32,
+    subgraph_size: i32,
+    edges: HashSet<(i32, i32)>,
+    encoding: ConnectivityEncoding
+) -> SubgraphConstraints {
+    let f = FormulaFactory::new();
+
+    let n = num_vertices as usize;
+    let k = subgraph_size as usize;
+
+    // --- Variables ---
+
+    let mut variables = VariableRegistry::new(&f, n, k);
+    if encoding == ConnectivityEncoding::Dense {
+        variables.allocate_edges(&f);
+        variables.allocate_links(&f);
+    }
+
+    // Vertices (true if in subgraph)
+    let v = |i: usize| variables.vertex(i);
+    // Edges (only allocated for the dense encoding)
+    let e = |i: usize, j: usize| variables.edge(i, j);
+    // Decisions (vertex picked per timestep)
+    let d = |t: usize, j: usize| variables.decision(t, j);
+
+    // --- Constraints ---
+
+    let mut constraints = Vec::<(ConstraintFamily, Constraint)>::new();
+
+    // Subgraph is non-empty
+    constraints.push((
+        ConstraintFamily::Decisions,
+        Constraint::Clause(Vec::from_iter((0..n).map(|i| v(i).pos_lit()))),
+    ));
+
+    // Edges are undirected
+    if encoding == ConnectivityEncoding::Dense {
+        for i in 0..n {
+            for j in 0..n {
+                if i == j {
+                    continue;
+                }
+
+                // e_i_j => e_j_i
+                constraints.push((
+                    ConstraintFamily::Edges,
+                    Constraint::Clause(
+                        vec![e(i, j).neg_lit(), e(j, i).pos_lit()]
+                    ),
+                ));
+            }
+        }
+    }
+
+    // Include vertices from decisions
+    for t in 0..k {
+        for i in 0..n {
+            // d_t_i => v_i
+            constraints.push((
+                ConstraintFamily::Decisions,
+                Constraint::Clause(vec![d(t, i).neg_lit(), v(i).pos_lit()]),
+            ));
+        }
+    }
+
+    // Include *only* vertices from decisions
+    for i in 0..n {
+        // v_i => d_0_i | d_1_i | ...
+        let mut clause = vec![v(i).neg_lit()];
+        clause.extend((0..k).map(|t| d(t, i).pos_lit()));
+
+        constraints.push((
+            ConstraintFamily::Decisions,
+            Constraint::Clause(clause),
+        ));
+    }
+
+    // One decision per timestep
+    for t in 0..k {
+        let vars = variables.decisions_at(t).to_vec();
+        let decisions_for_timestep = Constraint::Formula(f.exo(vars));
+        constraints.push((
+            ConstraintFamily::Decisions,
+            decisions_for_timestep,
+        ));
+    }
+
+    // Exactly k vertices, which the decisions already imply,
+    // but without a counter the solver can only prove there are
+    // no more subgraphs by ruling out every assignment of vertices
+    // to timesteps (a pigeonhole proof)
+    constraints.push((
+        ConstraintFamily::Decisions,
+        Constraint::Formula(
+            f.cc(CType::EQ, k as u64, variables.vertices().to_vec())
+        ),
+    ));
+
+    // For t >= 1, the node picked at each timestep
+    // must share an edge with one of the previous nodes
+    match encoding {
+        ConnectivityEncoding::Dense => {
+            // l_t0_j_i => d_t0_j & e_i_j
+            // ie "node j was picked at time t0 and shares an edge with node i"
+            for t0 in 0..k.saturating_sub(1) {
+                for j in 0..n {
+                    for i in 0..n {
+                        let link = variables.link(t0, j, i);
+
+                        constraints.push((
+                            ConstraintFamily::Connectivity,
+                            Constraint::Clause(
+                                vec![link.neg_lit(), d(t0, j).pos_lit()]
+                            ),
+                        ));
+                        constraints.push((
+                            ConstraintFamily::Connectivity,
+                            Constraint::Clause(
+                                vec![link.neg_lit(), e(i, j).pos_lit()]
+                            ),
+                        ));
+                    }
+                }
+            }
+
+            // """
+            // node i being picked at time t1, implies one of the following
+            //   node 0 was picked at time (t1 - 1) and shares an edge with node i
+            //   node 1 was picked at time (t1 - 1) and shares an edge with node i
+            //   ...
+            //   node n was picked at time (t1 - 1) and shares an edge with node i
+            //   node 0 was picked at time (t1 - 2) and shares an edge with node i
+            //   ...
+            //   node n was picked at time 0 and shares an edge with node i
+            // """
+            for t1 in 1..k {
+                for i in 0..n {
+                    let mut clause = vec![d(t1, i).neg_lit()];
+                    for t0 in 0..t1 {
+                        for j in 0..n {
+                            clause.push(variables.link(t0, j, i).pos_lit());
+                        }
+                    }
+
+                    constraints.push((
+                        ConstraintFamily::Connectivity,
+                        Constraint::Clause(clause),
+                    ));
+                }
+            }
+        }
+        ConnectivityEncoding::Sparse => {
+            let neighbours = neighbour_lists(n, &edges);
+
+            // "One of node i's neighbours was picked before t1"
+            for t1 in 1..k {
+                for (i, neighbours) in neighbours.iter().enumerate() {
+                    let mut clause = vec![d(t1, i).neg_lit()];
+                    for t0 in 0..t1 {
+                        for j in neighbours.iter() {
+                            clause.push(d(t0, *j).pos_lit());
+                        }
+                    }
+
+                    constraints.push((
+                        ConstraintFamily::Connectivity,
+                        Constraint::Clause(clause),
+                    ));
+                }
+            }
+        }
+    }
+
+    // The nodes picked at t0 and t1 can't be equal (assuming t0 != t1)
+    for i in 0..n {
+        for t0 in 0..k {
+            for t1 in t0 + 1..k {
+                constraints.push((
+                    ConstraintFamily::NoRepeat,
+                    Constraint::Clause(
+                        vec![d(t0, i).neg_lit(), d(t1, i).neg_lit()]
+                    ),
+                ));
+            }
+        }
+    }
+
+    // --- Init edge connections ---
+    if encoding == ConnectivityEncoding::Dense {
+        for i in 0..n {
+            for j in 0..n {
+                let edge = (i as i32, j as i32);
+                let edge_reversed = (j as i32, i as i32);
+
+                // Vertices have edge to self
+                let is_edge =
+                    i == j ||
+                    edges.contains(&edge) ||
+                    edges.contains(&edge_reversed);
+
+                let lit = if is_edge {
+                    e(i, j).pos_lit()
+                } else {
+                    e(i, j).neg_lit()
+                };
+                constraints.push((
+                    ConstraintFamily::Edges,
+                    Constraint::Clause(vec![lit]),
+                ));
+            }
+        }
+    }
+
+    SubgraphConstraints {
+        factory: f,
+        variables,
+        constraints,
+        num_vertices,
+        subgraph_size,
+    }
+}
+
+/**
+ * Neighbours of each vertex, ascending and excluding itself
+ */
+pub fn neighbour_lists(
+    num_vertices: usize,
+    edges: &HashSet<(i32, i32)>
+) -> Vec<Vec<usize>> {
+    Vec::from_iter(
+        (0..num_vertices).map(|i|
+            Vec::from_iter(
+                (0..num_vertices).filter(|j| {
+                    let edge = (i as i32, *j as i32);
+                    let edge_reversed = (*j as i32, i as i32);
+
+                    i != *j &&
+                        (edges.contains(&edge) ||
+                            edges.contains(&edge_reversed))
+                })
+            )
+        )
+    )
+}
+
+/**
+ * Each picked vertex must share an edge with at least min_degree other picked vertices,
+ * so eg a chain of vertices that each only share an edge with the next doesn't qualify
+ *
+ * Connectivity only implies a min degree of 1 (for subgraphs of 2 or more vertices).
+ * Higher minimums count the picked neighbours of each vertex i with a sequential counter,
+ * where c_{i}_{j}_{r} means "at least r of the first j neighbours are picked":
+ *   c_i_j_r => c_i_(j-1)_r | x_j
+ *   c_i_j_r => c_i_(j-1)_r | c_i_(j-1)_(r-1)
+ *   v_i => c_i_d_m
+ * for neighbours x_1 ... x_d and min degree m.
+ * Only the upward direction is needed, since the counters are only ever required to be true.
+ */
+pub fn build_degree_constraints(
+    min_degree: usize,
+    edges: &HashSet<(i32, i32)>,
+    subgraph_constraints: &mut SubgraphConstraints
+) {
+    if min_degree == 0 {
+        return;
+    }
+
+    let num_vertices = subgraph_constraints.num_vertices as usize;
+    let neighbours = neighbour_lists(num_vertices, edges);
+    let degrees = Vec::from_iter(neighbours.iter().map(|ns| ns.len()));
+
+    let SubgraphConstraints {
+        factory: f,
+        variables,
+        constraints,
+        ..
+    } = subgraph_constraints;
+    variables.allocate_counters(f, &degrees, min_degree);
+
+    let m = min_degree;
+    for (i, neighbours) in neighbours.iter().enumerate() {
+        let v = variables.vertex(i);
+        let d = neighbours.len();
+
+        // Can't be picked at all
+        if d < m {
+            constraints.push((
+                ConstraintFamily::MinDegree,
+                Constraint::Clause(vec![v.neg_lit()]),
+            ));
+            continue;
+        }
+
+        if m == 1 {
+            let mut clause = vec![v.neg_lit()];
+            clause.extend(
+                neighbours.iter().map(|j| variables.vertex(*j).pos_lit())
+            );
+
+            constraints.push((
+                ConstraintFamily::MinDegree,
+                Constraint::Clause(clause),
+            ));
+            continue;
+        }
+
+        let c = |j: usize, r: usize| variables.counter(i, j, r);
+
+        for (j, x) in (1..=d).zip(neighbours.iter()) {
+            for r in 1..=j.min(m) {
+                let counter = c(j, r).unwrap().neg_lit();
+                // None if fewer than r of the first j - 1 can be picked
+                let before = c(j - 1, r).map(|c| c.pos_lit());
+
+                let mut clause = vec![counter];
+                clause.extend(before);
+                clause.push(variables.vertex(*x).pos_lit());
+                constraints.push((
+                    ConstraintFamily::MinDegree,
+                    Constraint::Clause(clause),
+                ));
+
+                if r >= 2 {
+                    let mut clause = vec![counter];
+                    clause.extend(before);
+                    clause.push(c(j - 1, r - 1).unwrap().pos_lit());
+                    constraints.push((
+                        ConstraintFamily::MinDegree,
+                        Constraint::Clause(clause),
+                    ));
+                }
+            }
+        }
+
+        constraints.push((
+            ConstraintFamily::MinDegree,
+            Constraint::Clause(
+                vec![v.neg_lit(), c(d, m).unwrap().pos_lit()]
+            ),
+        ));
+    }
+}
+
+/**
+ * Whether a slot whose options are every vertex can be left out of the encoding
+ */
+pub fn is_universal_slot(
+    options: &[usize],
+    num_vertices: usize,
+    subgraph_size: usize,
+    num_slots: usize
+) -> bool {
+    options.len() >= num_vertices && subgraph_size >= num_slots
+}
+
+/**
+ * Each slot must be filled by a different vertex of the subgraph
+ *
+ * Every option of a slot gets an assignment variable s_{slot}_{vertex},
+ * which can only be true if the vertex is picked.
+ * Each slot needs one of its assignments to be true,
+ * and each vertex can be assigned to at most one slot.
+ *
+ * For example, with the slots
+ *   A = { 1, 2, 3 }
+ *   B = { 1, 4, 5 }
+ *
+ * (1|2|3) & (1|4|5) alone would accept the team (1,_),
+ * but vertex 1 can't be assigned to both A and B
+ * so the team also needs one of 2, 3, 4 or 5.
+ *
+ * By Hall's theorem these assignments exist iff the team has distinct champions for the slots,
+ * and the encoding grows with the total number of options rather than their overlap.
+ */
+pub fn build_slot_constraints(
+    slot_options: &[Vec<usize>],
+    subgraph_constraints: &mut SubgraphConstraints
+) {
+    // @todo: can this optimization be generalized?
+    // Slots that contain all champions don't need to be constraints
+    // when there are at least as many team members as slots,
+    // since a member the other slots don't need is left for them
+    let num_vertices = subgraph_constraints.num_vertices as usize;
+    let subgraph_size = subgraph_constraints.subgraph_size as usize;
+    let num_slots = slot_options.len();
+    let slot_options = Vec::from_iter(
+        slot_options
+            .iter()
+            .filter(|opts|
+                !is_universal_slot(opts, num_vertices, subgraph_size, num_slots)
+            )
+            .cloned()
+    );
+
+    let SubgraphConstraints {
+        factory: f,
+        variables,
+        constraints,
+        ..
+    } = subgraph_constraints;
+    variables.allocate_slots(f, &slot_options);
+
+    // Each slot is assigned one of its options, which must be in the team
+    for (s, options) in slot_options.iter().enumerate() {
+        let a = |v: usize| variables.slot(s, v).unwrap();
+
+        // s_s_0 | s_s_1 | ...
+        constraints.push((
+            ConstraintFamily::SlotCoverage,
+            Constraint::Clause(
+                Vec::from_iter(options.iter().map(|v| a(*v).pos_lit()))
+            ),
+        ));
+
+        // s_s_v => v_v
+        for v in options.iter() {
+            constraints.push((
+                ConstraintFamily::SlotCoverage,
+                Constraint::Clause(
+                    vec![a(*v).neg_lit(), variables.vertex(*v).pos_lit()]
+                ),
+            ));
+        }
+    }
+
+    // A single champion cannot fill multiple slots
+    for v in 0..num_vertices {
+        let assignments = Vec::from_iter(
+            (0..slot_options.len()).filter_map(|s| variables.slot(s, v))
+        );
+        if assignments.len() <= 1 {
+            continue;
+        }
+
+        constraints.push((
+            ConstraintFamily::SlotDedupe,
+            Constraint::Formula(f.amo(assignments)),
+        ));
+    }
+}
@@ -0,0 +1,670 @@
+use std::collections::HashMap;
+
+use logicng::formulas::{ FormulaFactory, Literal, Variable };
+
+use super::TeamSolverBackend;
+
+// Literals are encoded as (var << 1) | is_negative
+type Lit = usize;
+
+const UNASSIGNED: u8 = 2;
+
+fn negate(lit: Lit) -> Lit {
+    lit ^ 1
+}
+
+fn var_of(lit: Lit) -> usize {
+    lit >> 1
+}
+
+/**
+ * Minimal pure-Rust CDCL solver
+ *
+ * Two watched literals, first-UIP clause learning and activity-based branching.
+ * There are no restarts or learnt clause deletion,
+ * it mostly exists as a baseline to benchmark other backends against.
+ *
+ * Assumptions are handled like in MiniSat,
+ * ie they're the first decisions and conflicts may backjump past them.
+ *
+ * Kept in-tree rather than pulling in a solver crate (eg varisat or splr)
+ * since it has no dependencies, so it costs the wasm bundle little,
+ * and it counts the conflicts shown by the search progress.
+ * If it ever needs restarts or clause deletion to be useful,
+ * switching to one of those crates is the better option.
+ */
+pub struct CdclBackend {
+    // LogicNG variable <-> solver variable
+    indices: HashMap<Variable, usize>,
+    variables: Vec<Variable>,
+
+    clauses: Vec<Vec<Lit>>,
+    // Clauses watching each literal (the first two literals of a clause are watched)
+    watches: Vec<Vec<usize>>,
+
+    // Per variable
+    values: Vec<u8>,
+    levels: Vec<usize>,
+    reasons: Vec<Option<usize>>,
+    activity: Vec<f64>,
+    seen: Vec<bool>,
+
+    trail: Vec<Lit>,
+    trail_lim: Vec<usize>,
+    queue_head: usize,
+    activity_inc: f64,
+
+    // Set when the clauses are unsatisfiable regardless of assumptions
+    is_unsat: bool,
+    model: Option<Vec<bool>>,
+
+    // Across all solve() calls
+    conflicts: u64,
+}
+
+impl Default for CdclBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CdclBackend {
+    pub fn new() -> Self {
+        Self {
+            indices: HashMap::new(),
+            variables: vec![],
+            clauses: vec![],
+            watches: vec![],
+            values: vec![],
+            levels: vec![],
+            reasons: vec![],
+            activity: vec![],
+            seen: vec![],
+            trail: vec![],
+            trail_lim: vec![],
+            queue_head: 0,
+            activity_inc: 1.0,
+            is_unsat: false,
+            model: None,
+            conflicts: 0,
+        }
+    }
+
+    fn lit_for(&mut self, lit: &Literal) -> Lit {
+        let var = lit.variable();
+
+        let idx = match self.indices.get(&var) {
+            Some(idx) => *idx,
+            None => {
+                let idx = self.variables.len();
+
+                self.indices.insert(var, idx);
+                self.variables.push(var);
+                self.watches.push(vec![]);
+                self.watches.push(vec![]);
+                self.values.push(UNASSIGNED);
+                self.levels.push(0);
+                self.reasons.push(None);
+                self.activity.push(0.0);
+                self.seen.push(false);
+
+                idx
+            }
+        };
+
+        (idx << 1) | (!lit.phase() as usize)
+    }
+
+    /**
+     * 1 if true, 0 if false, UNASSIGNED otherwise
+     */
+    fn value(&self, lit: Lit) -> u8 {
+        match self.values[var_of(lit)] {
+            UNASSIGNED => UNASSIGNED,
+            v => v ^ ((lit & 1) as u8),
+        }
+    }
+
+    fn decision_level(&self) -> usize {
+        self.trail_lim.len()
+    }
+
+    fn enqueue(&mut self, lit: Lit, reason: Option<usize>) {
+        let var = var_of(lit);
+
+        self.values[var] = ((lit & 1) ^ 1) as u8;
+        self.levels[var] = self.decision_level();
+        self.reasons[var] = reason;
+        self.trail.push(lit);
+    }
+
+    fn cancel_until(&mut self, level: usize) {
+        if self.decision_level() <= level {
+            return;
+        }
+
+        let start = self.trail_lim[level];
+        for lit in self.trail.drain(start..) {
+            self.values[var_of(lit)] = UNASSIGNED;
+        }
+
+        self.trail_lim.truncate(level);
+        self.queue_head = self.trail.len();
+    }
+
+    fn attach(&mut self, clause: Vec<Lit>) -> usize {
+        let idx = self.clauses.len();
+
+        self.watches[clause[0]].push(idx);
+        self.watches[clause[1]].push(idx);
+        self.clauses.push(clause);
+
+        idx
+    }
+
+    /**
+     * Returns the index of a conflicting clause, if any
+     */
+    fn propagate(&mut self) -> Option<usize> {
+        while self.queue_head < self.trail.len() {
+            let false_lit = negate(self.trail[self.queue_head]);
+            self.queue_head += 1;
+
+            let watchers = std::mem::take(&mut self.watches[false_lit]);
+            let mut kept = Vec::with_capacity(watchers.len());
+            let mut conflict = None;
+
+            for (pos, &ci) in watchers.iter().enumerate() {
+                if conflict.is_some() {
+                    kept.extend_from_slice(&watchers[pos..]);
+                    break;
+                }
+
+                // Keep the false literal in the second slot
+                if self.clauses[ci][0] == false_lit {
+                    self.clauses[ci].swap(0, 1);
+                }
+
+                let first = self.clauses[ci][0];
+                if self.value(first) == 1 {
+                    kept.push(ci);
+                    continue;
+                }
+
+                // Look for another literal to watch
+                let replacement = (2..self.clauses[ci].len()).find(|k|
+                    self.value(self.clauses[ci][*k]) != 0
+                );
+
+                match replacement {
+                    Some(k) => {
+                        self.clauses[ci].swap(1, k);
+                        let watched = self.clauses[ci][1];
+                        self.watches[watched].push(ci);
+                    }
+                    None => {
+                        kept.push(ci);
+
+                        if self.value(first) == 0 {
+                            conflict = Some(ci);
+                        } else {
+                            self.enqueue(first, Some(ci));
+                        }
+                    }
+                }
+            }
+
+            self.watches[false_lit] = kept;
+
+            if conflict.is_some() {
+                return conflict;
+            }
+        }
+
+        None
+    }
+
+    /**
+     * First-UIP conflict analysis
+     * Returns the learnt clause (asserting literal first) and the level to backjump to
+     */
+    fn analyze(&mut self, conflict: usize) -> (Vec<Lit>, usize) {
+        let mut learnt: Vec<Lit> = vec![0];
+        let mut num_pending = 0;
+        let mut implied: Option<Lit> = None;
+        let mut reason = conflict;
+        let mut trail_idx = self.trail.len();
+
+        loop {
+            // The implied literal of a reason clause is always in the first slot
+            let skip = if implied.is_some() { 1 } else { 0 };
+
+            for k in skip..self.clauses[reason].len() {
+                let lit = self.clauses[reason][k];
+                let var = var_of(lit);
+
+                if self.seen[var] || self.levels[var] == 0 {
+                    continue;
+                }
+
+                self.seen[var] = true;
+                self.bump(var);
+
+                if self.levels[var] >= self.decision_level() {
+                    num_pending += 1;
+                } else {
+                    learnt.push(lit);
+                }
+            }
+
+            // Walk back along the trail to the next literal involved in the conflict
+            loop {
+                trail_idx -= 1;
+                if self.seen[var_of(self.trail[trail_idx])] {
+                    break;
+                }
+            }
+
+            let lit = self.trail[trail_idx];
+            self.seen[var_of(lit)] = false;
+            implied = Some(lit);
+            num_pending -= 1;
+
+            if num_pending == 0 {
+                break;
+            }
+
+            reason = self.reasons[var_of(lit)].unwrap();
+        }
+
+        learnt[0] = negate(implied.unwrap());
+
+        for lit in learnt.iter() {
+            self.seen[var_of(*lit)] = false;
+        }
+
+        // Watch the literal with the highest level after the asserting literal
+        let mut backjump_level = 0;
+        if learnt.len() > 1 {
+            let mut max_idx = 1;
+            for k in 2..learnt.len() {
+                if
+                    self.levels[var_of(learnt[k])] >
+                    self.levels[var_of(learnt[max_idx])]
+                {
+                    max_idx = k;
+                }
+            }
+
+            learnt.swap(1, max_idx);
+            backjump_level = self.levels[var_of(learnt[1])];
+        }
+
+        self.activity_inc /= 0.95;
+
+        (learnt, backjump_level)
+    }
+
+    fn bump(&mut self, var: usize) {
+        self.activity[var] += self.activity_inc;
+
+        if self.activity[var] > 1e100 {
+            for a in self.activity.iter_mut() {
+                *a *= 1e-100;
+            }
+            self.activity_inc *= 1e-100;
+        }
+    }
+
+    fn pick_branch_var(&self) -> Option<usize> {
+        let mut best: Option<usize> = None;
+
+        for var in 0..self.values.len() {
+            if self.values[var] != UNASSIGNED {
+                continue;
+            }
+
+            match best {
+                Some(b) if self.activity[b] >= self.activity[var] => {}
+                _ => {
+                    best = Some(var);
+                }
+            }
+        }
+
+        best
+    }
+}
+
+impl TeamSolverBackend for CdclBackend {
+    fn fresh(&self) -> Box<dyn TeamSolverBackend> {
+        Box::new(Self::new())
+    }
+
+    fn add_clause(
+        &mut self,
+        clause: &[Literal],
+        _f: &FormulaFactory
+    ) {
+        if self.is_unsat {
+            return;
+        }
+
+        self.cancel_until(0);
+        self.model = None;
+
+        let mut lits = Vec::<Lit>::with_capacity(clause.len());
+        for lit in clause {
+            let lit = self.lit_for(lit);
+
+            // Already satisfied or tautology
+            if self.value(lit) == 1 || lits.contains(&negate(lit)) {
+                return;
+            }
+
+            // Drop duplicates and literals that are false at the top level
+            if self.value(lit) == 0 || lits.contains(&lit) {
+                continue;
+            }
+
+            lits.push(lit);
+        }
+
+        match lits.len() {
+            0 => {
+                self.is_unsat = true;
+            }
+            1 => {
+                self.enqueue(lits[0], None);
+
+                if self.propagate().is_some() {
+                    self.is_unsat = true;
+                }
+            }
+            _ => {
+                self.attach(lits);
+            }
+        }
+    }
+
+    fn solve(&mut self, assumptions: &[Literal]) -> bool {
+        self.model = None;
+
+        if self.is_unsat {
+            return false;
+        }
+
+        let assumptions: Vec<Lit> = assumptions
+            .iter()
+            .map(|lit| self.lit_for(lit))
+            .collect();
+
+        loop {
+            if let Some(conflict) = self.propagate() {
+                self.conflicts += 1;
+
+                if self.decision_level() == 0 {
+                    self.is_unsat = true;
+                    return false;
+                }
+
+                let (learnt, backjump_level) = self.analyze(conflict);
+                self.cancel_until(backjump_level);
+
+                if learnt.len() == 1 {
+                    self.enqueue(learnt[0], None);
+                } else {
+                    let asserting = learnt[0];
+                    let idx = self.attach(learnt);
+                    self.enqueue(asserting, Some(idx));
+                }
+
+                continue;
+            }
+
+            // Assumptions are decided first, one per level
+            let level = self.decision_level();
+            if level < assumptions.len() {
+                let lit = assumptions[level];
+
+                match self.value(lit) {
+                    1 => {
+                        self.trail_lim.push(self.trail.len());
+                    }
+                    0 => {
+                        self.cancel_until(0);
+                        return false;
+                    }
+                    _ => {
+                        self.trail_lim.push(self.trail.len());
+                        self.enqueue(lit, None);
+                    }
+                }
+
+                continue;
+            }
+
+            match self.pick_branch_var() {
+                Some(var) => {
+                    // Most variables (eg vertices) are false in a solution
+                    self.trail_lim.push(self.trail.len());
+                    self.enqueue((var << 1) | 1, None);
+                }
+                None => {
+                    self.model = Some(
+                        self.values
+                            .iter()
+                            .map(|v| *v == 1)
+                            .collect()
+                    );
+
+                    self.cancel_until(0);
+                    return true;
+                }
+            }
+        }
+    }
+
+    fn model(&self, variables: &[Variable]) -> Option<Vec<Literal>> {
+        let model = self.model.as_ref()?;
+
+        Some(
+            variables
+                .iter()
+                .filter_map(|var| {
+                    let idx = self.indices.get(var)?;
+                    Some(Literal::new(*var, model[*idx]))
+                })
+                .collect()
+        )
+    }
+
+    fn block_model(
+        &mut self,
+        variables: &[Variable],
+        f: &FormulaFactory
+    ) {
+        if let Some(model) = self.model(variables) {
+            let clause: Vec<Literal> = model
+                .iter()
+                .map(|lit| lit.negate())
+                .collect();
+
+            self.add_clause(&clause, f);
+        }
+    }
+
+    fn num_conflicts(&self) -> Option<u64> {
+        Some(self.conflicts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use logicng::formulas::FormulaFactory;
+
+    use std::collections::HashSet;
+
+    use crate::sat::{
+        build_ab_graph,
+        build_kite_graph,
+        build_square_graph,
+        ConnectedSubgraphProblem,
+        SubgraphConstraints,
+        Solution,
+        SubgraphSearch,
+        SubgraphSolver,
+        TeamSolverBackend,
+    };
+
+    use super::CdclBackend;
+
+    fn collect_sorted(solver: &mut SubgraphSolver) -> Vec<Solution> {
+        let mut teams = vec![];
+
+        // Solutions are already sorted
+        while let Some(sol) = solver.next() {
+            teams.push(sol);
+        }
+
+        teams.sort();
+        teams
+    }
+
+    type Fixture = Box<dyn Fn() -> SubgraphConstraints>;
+
+    fn fixtures() -> Vec<Fixture> {
+        let mut result: Vec<Fixture> = vec![];
+
+        for k in 1..=2 {
+            result.push(Box::new(move || build_ab_graph(k)));
+        }
+        for k in 1..=4 {
+            result.push(Box::new(move || build_square_graph(k)));
+        }
+        for k in 1..=5 {
+            result.push(Box::new(move || build_kite_graph(k)));
+        }
+
+        result
+    }
+
+    #[test]
+    fn test_matches_minisat() {
+        for build in fixtures() {
+            let mut expected = SubgraphSolver::new(build());
+            let mut actual = SubgraphSolver::new_with_backend(
+                build(),
+                Box::new(CdclBackend::new())
+            );
+
+            assert_eq!(
+                collect_sorted(&mut actual),
+                collect_sorted(&mut expected)
+            );
+        }
+    }
+
+    /**
+     * Which teams are returned depends on the order they're found in,
+     * so only check that both backends return a maximal diverse set,
+     * ie every team is either returned or too similar to one that was
+     */
+    #[test]
+    fn test_diversity_matches_minisat() {
+        for build in fixtures() {
+            let all = collect_sorted(&mut SubgraphSolver::new(build()));
+
+            // Teams can't differ by more than their size
+            let size = all.first().map_or(0, |team| team.len());
+            for diversity in 2..=size.min(3) {
+                let mut minisat = SubgraphSolver::new(build());
+                minisat.diversity = diversity;
+                let mut cdcl = SubgraphSolver::new_with_backend(
+                    build(),
+                    Box::new(CdclBackend::new())
+                );
+                cdcl.diversity = diversity;
+
+                for teams in [
+                    collect_sorted(&mut minisat),
+                    collect_sorted(&mut cdcl),
+                ] {
+                    let num_different = |a: &Solution, b: &Solution| {
+                        a.iter().filter(|v| !b.contains(v)).count()
+                    };
+
+                    for (idx, a) in teams.iter().enumerate() {
+                        for b in teams[idx + 1..].iter() {
+                            assert!(num_different(a, b) >= diversity);
+                        }
+                    }
+
+                    for team in all.iter() {
+                        assert!(
+                            teams
+                                .iter()
+                                .any(|t| num_different(t, team) < diversity),
+                            "{:?} could have been returned",
+                            team
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_side_constraints_match_minisat() {
+        // Kite, a - b, a - c, b - d, c - d, d - e
+        let mut problem = ConnectedSubgraphProblem::new(
+            0..5,
+            [(0, 1), (0, 2), (1, 3), (2, 3), (3, 4)],
+            2..=4
+        ).unwrap();
+        problem.at_most(1, &[1, 2]).unwrap();
+        problem.at_least(1, &[0, 4]).unwrap();
+        problem.add_slot(&[1, 3]).unwrap();
+        problem.min_degree = 1;
+
+        let expected = HashSet::<Vec<usize>>::from_iter(problem.solutions());
+        let actual = HashSet::<Vec<usize>>::from_iter(
+            problem.solutions_with(|| Box::new(CdclBackend::new()))
+        );
+
+        assert!(!expected.is_empty());
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_assumptions() {
+        let f = FormulaFactory::new();
+        let a = f.var("a");
+        let b = f.var("b");
+
+        let mut solver = CdclBackend::new();
+
+        // a | b, ~a | ~b
+        solver.add_clause(&[a.pos_lit(), b.pos_lit()], &f);
+        solver.add_clause(&[a.neg_lit(), b.neg_lit()], &f);
+
+        assert!(solver.solve(&[a.pos_lit()]));
+        assert_eq!(
+            solver.model(&[a, b]),
+            Some(vec![a.pos_lit(), b.neg_lit()])
+        );
+
+        assert!(!solver.solve(&[a.pos_lit(), b.pos_lit()]));
+
+        // Assumptions don't persist
+        assert!(solver.solve(&[b.pos_lit()]));
+        assert_eq!(
+            solver.model(&[a, b]),
+            Some(vec![a.neg_lit(), b.pos_lit()])
+        );
+
+        solver.add_clause(&[a.pos_lit()], &f);
+        solver.add_clause(&[b.pos_lit()], &f);
+        assert!(!solver.solve(&[]));
+    }
+}
@@ -0,0 +1,315 @@
+use std::collections::{ hash_map::Entry, HashMap };
+
+use logicng::formulas::{
+    EncodedFormula,
+    FormulaFactory,
+    Literal,
+    Variable,
+};
+
+use super::{
+    Constraint,
+    ConstraintFamily,
+    SubgraphConstraints,
+    VariableRegistry,
+};
+
+/**
+ * Writes the constraints as a DIMACS CNF file, eg
+ *
+ *   c num_vertices 2
+ *   c subgraph_size 1
+ *   c var 1 v0
+ *   c var 2 v1
+ *   ...
+ *   p cnf 8 11
+ *   1 2 0
+ *   -1 3 0
+ *   ...
+ *
+ * The "c var" comments map each DIMACS variable back to its name
+ * (v{i}, e_{i}_{j}, d_{t}_{j}, ..., or aux{n} for variables introduced by the CNF conversion)
+ */
+pub fn write_dimacs(constraints: &SubgraphConstraints) -> String {
+    let f = &constraints.factory;
+    let clauses = constraints.clauses();
+
+    // Number the variables in order of appearance
+    let mut indices = HashMap::<Variable, usize>::new();
+    let mut names = Vec::<String>::new();
+    for lit in clauses.iter().flatten() {
+        let var = lit.variable();
+
+        if let Entry::Vacant(entry) = indices.entry(var) {
+            let name = match var.aux_name(f) {
+                Some(_) => format!("aux{}", names.len() + 1),
+                None => var.name(f).into_owned(),
+            };
+
+            names.push(name);
+            entry.insert(names.len());
+        }
+    }
+
+    let mut lines = Vec::<String>::new();
+
+    lines.push(
+        format!("c num_vertices {}", constraints.num_vertices)
+    );
+    lines.push(
+        format!("c subgraph_size {}", constraints.subgraph_size)
+    );
+    for (idx, name) in names.iter().enumerate() {
+        lines.push(format!("c var {} {}", idx + 1, name));
+    }
+
+    lines.push(format!("p cnf {} {}", names.len(), clauses.len()));
+    for clause in clauses.iter() {
+        let mut line = String::new();
+
+        for lit in clause {
+            let idx = indices[&lit.variable()] as i64;
+            let signed = if lit.phase() { idx } else { -idx };
+            line.push_str(&format!("{} ", signed));
+        }
+
+        line.push('0');
+        lines.push(line);
+    }
+
+    lines.join("\n") + "\n"
+}
+
+/**
+ * Inverse of write_dimacs
+ *
+ * Variables without a "c var" comment are named x{idx}.
+ * The num_vertices / subgraph_size comments are required since the solver needs them
+ * to know which variables make up a solution.
+ */
+pub fn read_dimacs(
+    input: &str
+) -> Result<SubgraphConstraints, String> {
+    let f = FormulaFactory::new();
+
+    let mut num_vertices: Option<i32> = None;
+    let mut subgraph_size: Option<i32> = None;
+    let mut names = HashMap::<usize, String>::new();
+    let mut num_vars: Option<usize> = None;
+
+    let mut clauses = Vec::<Vec<i64>>::new();
+    let mut clause = Vec::<i64>::new();
+
+    for (line_idx, line) in input.lines().enumerate() {
+        let line = line.trim();
+        let err = |msg: &str| format!("Line {}: {}", line_idx + 1, msg);
+
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(comment) = line.strip_prefix('c') {
+            let parts: Vec<&str> = comment.split_whitespace().collect();
+
+            match parts.as_slice() {
+                ["num_vertices", n] => {
+                    num_vertices = Some(
+                        n
+                            .parse()
+                            .map_err(|_| err("invalid num_vertices"))?
+                    );
+                }
+                ["subgraph_size", n] => {
+                    subgraph_size = Some(
+                        n
+                            .parse()
+                            .map_err(|_| err("invalid subgraph_size"))?
+                    );
+                }
+                ["var", idx, name] => {
+                    let idx: usize = idx
+                        .parse()
+                        .map_err(|_| err("invalid variable index"))?;
+                    names.insert(idx, name.to_string());
+                }
+                _ => {}
+            }
+
+            continue;
+        }
+
+        if let Some(header) = line.strip_prefix('p') {
+            let parts: Vec<&str> = header.split_whitespace().collect();
+
+            match parts.as_slice() {
+                ["cnf", vars, _] => {
+                    num_vars = Some(
+                        vars.parse().map_err(|_| err("invalid header"))?
+                    );
+                }
+                _ => {
+                    return Err(
+                        err("expected header like 'p cnf <vars> <clauses>'")
+                    );
+                }
+            }
+
+            continue;
+        }
+
+        let max_var = num_vars.ok_or_else(||
+            err("clause before header")
+        )?;
+
+        for token in line.split_whitespace() {
+            let lit: i64 = token
+                .parse()
+                .map_err(|_| err("invalid literal"))?;
+
+            if lit == 0 {
+                clauses.push(clause);
+                clause = vec![];
+            } else if lit.unsigned_abs() as usize > max_var {
+                return Err(
+                    err("literal exceeds variable count in header")
+                );
+            } else {
+                clause.push(lit);
+            }
+        }
+    }
+
+    if !clause.is_empty() {
+        return Err("Last clause is not terminated by 0".to_string());
+    }
+
+    let num_vertices = num_vertices.ok_or(
+        "Missing 'c num_vertices' comment"
+    )?;
+    let subgraph_size = subgraph_size.ok_or(
+        "Missing 'c subgraph_size' comment"
+    )?;
+
+    // Allocated before the clauses so named variables (v{i} etc)
+    // resolve to the registry's variables
+    let variables = VariableRegistry::new(
+        &f,
+        num_vertices.max(0) as usize,
+        subgraph_size.max(0) as usize
+    );
+
+    let constraints: Vec<(ConstraintFamily, Constraint)> = clauses
+        .iter()
+        .map(|clause| {
+            let lits: Vec<Literal> = clause
+                .iter()
+                .map(|lit| {
+                    let idx = lit.unsigned_abs() as usize;
+                    let name = names
+                        .get(&idx)
+                        .cloned()
+                        .unwrap_or_else(|| format!("x{}", idx));
+
+                    f.lit(&name, *lit > 0)
+                })
+                .collect();
+
+            (ConstraintFamily::Imported, Constraint::Clause(lits))
+        })
+        .collect();
+
+    Ok(SubgraphConstraints {
+        factory: f,
+        variables,
+        constraints,
+        num_vertices,
+        subgraph_size,
+    })
+}
+
+/**
+ * Splits a formula that is already in CNF into its clauses
+ */
+pub fn cnf_clauses(
+    cnf: EncodedFormula,
+    f: &FormulaFactory
+) -> Vec<Vec<Literal>> {
+    if cnf.is_verum() {
+        vec![]
+    } else if cnf.is_falsum() {
+        vec![vec![]]
+    } else if cnf.is_and() {
+        cnf.operands(f)
+            .into_iter()
+            .map(|clause| clause.literals_for_clause_or_term(f))
+            .collect()
+    } else {
+        vec![cnf.literals_for_clause_or_term(f)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sat::{
+        build_kite_graph,
+        build_square_graph,
+        Solution,
+        SubgraphSearch,
+        SubgraphSolver,
+    };
+
+    use super::{ read_dimacs, write_dimacs };
+
+    fn collect_sorted(solver: &mut SubgraphSolver) -> Vec<Solution> {
+        let mut teams = vec![];
+
+        // Solutions are already sorted
+        while let Some(sol) = solver.next() {
+            teams.push(sol);
+        }
+
+        teams.sort();
+        teams
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let graphs = [build_square_graph(2), build_kite_graph(3)];
+
+        for constraints in graphs {
+            let dimacs = write_dimacs(&constraints);
+            let imported = read_dimacs(&dimacs).unwrap();
+
+            let mut expected = SubgraphSolver::new(constraints);
+            let mut actual = SubgraphSolver::new(imported);
+
+            assert_eq!(
+                collect_sorted(&mut actual),
+                collect_sorted(&mut expected)
+            );
+        }
+    }
+
+    #[test]
+    fn test_name_map() {
+        let dimacs = write_dimacs(&build_square_graph(2));
+
+        assert!(dimacs.contains("c num_vertices 4\n"));
+        assert!(dimacs.contains("c subgraph_size 2\n"));
+        for name in ["v3", "e_0_1", "d_1_2"] {
+            assert!(
+                dimacs.lines().any(|line| line.ends_with(name)),
+                "Expected {} in name map",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(read_dimacs("p cnf 1 1\n2 0\n").is_err());
+        assert!(read_dimacs("p cnf 1 1\n1\n").is_err());
+        assert!(read_dimacs("1 0\n").is_err());
+        assert!(read_dimacs("p cnf 1 1\n1 0\n").is_err());
+    }
+}
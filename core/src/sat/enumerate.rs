@@ -0,0 +1,514 @@
+use std::collections::HashSet;
+
+use web_time::Instant;
+
+use crate::team_mask::TeamMask;
+
+use super::{
+    num_combinations,
+    Backbone,
+    Solution,
+    SolverProgress,
+    SubgraphConstraints,
+};
+
+/**
+ * Anything that lazily enumerates the teams of one component,
+ * so a search doesn't care whether they come from a SAT solver
+ */
+pub trait SubgraphSearch {
+    fn next(&mut self) -> Option<Solution>;
+
+    fn block_solution(&mut self, vertices: &[usize]);
+
+    fn partition(&mut self, index: usize, count: usize);
+
+    fn progress(&self) -> SolverProgress;
+
+    /**
+     * Vertices in every team or in none, None if there are no teams
+     * (ignores blocked solutions and partitions)
+     */
+    fn backbone(&self) -> Option<Backbone>;
+
+    /**
+     * None if the teams aren't found by a SAT solver
+     */
+    fn constraints(&self) -> Option<&SubgraphConstraints> {
+        None
+    }
+}
+
+/**
+ * Enumerates the connected subgraphs of a fixed size directly,
+ * for searches that only need connectivity (no slots, no diversity)
+ *
+ * Uses ESU (Wernicke 2006): a subgraph is grown from its lowest vertex (the root)
+ * by only ever adding vertices above the root that are adjacent to the subgraph,
+ * and a vertex that was adjacent to an earlier vertex of the subgraph
+ * is only offered by that earlier vertex, so each subgraph is found exactly once.
+ *
+ * Sweeps the roots in ascending order like SubgraphSolver,
+ * so progress and partitions work the same way.
+ */
+pub struct SubgraphEnumerator {
+    // Neighbours of each vertex, excluding itself
+    neighbours: Vec<TeamMask>,
+    subgraph_size: usize,
+
+    // Root of the current sweep
+    root: usize,
+    // Whether the stack belongs to the current root
+    is_sweeping: bool,
+    stack: Vec<Extension>,
+
+    // Number of vertex sets of the subgraph size with each root,
+    // same as SubgraphSolver
+    root_weights: Vec<f64>,
+    blocked: HashSet<TeamMask>,
+    solutions_found: usize,
+    started_at: Instant,
+}
+
+/**
+ * A subgraph that's still being grown, with the vertices it can grow by
+ */
+struct Extension {
+    subgraph: Vec<usize>,
+    // Subgraph and every vertex adjacent to it
+    neighbourhood: TeamMask,
+    candidates: Vec<usize>,
+}
+
+impl SubgraphEnumerator {
+    pub fn new(
+        num_vertices: usize,
+        subgraph_size: usize,
+        edges: &HashSet<(i32, i32)>
+    ) -> Self {
+        let mut neighbours = vec![TeamMask::new(); num_vertices];
+        for (a, b) in edges.iter() {
+            let (a, b) = (*a as usize, *b as usize);
+            if a != b {
+                neighbours[a].insert(b);
+                neighbours[b].insert(a);
+            }
+        }
+
+        let root_weights = Vec::from_iter(
+            (0..num_vertices).map(|root|
+                num_combinations(
+                    num_vertices - root - 1,
+                    subgraph_size.saturating_sub(1)
+                )
+            )
+        );
+
+        Self {
+            neighbours,
+            subgraph_size,
+            root: 0,
+            is_sweeping: false,
+            stack: vec![],
+            root_weights,
+            blocked: HashSet::new(),
+            solutions_found: 0,
+            started_at: Instant::now(),
+        }
+    }
+
+    fn start_sweep(&mut self) {
+        let root = self.root;
+
+        let mut neighbourhood = self.neighbours[root].clone();
+        neighbourhood.insert(root);
+
+        self.stack.push(Extension {
+            subgraph: vec![root],
+            neighbourhood,
+            candidates: Vec::from_iter(
+                self.neighbours[root].iter().filter(|u| *u > root)
+            ),
+        });
+        self.is_sweeping = true;
+    }
+
+    /**
+     * Same as SubgraphSolver::progress, without conflicts
+     */
+    pub fn progress(&self) -> SolverProgress {
+        let swept = self.root.min(self.root_weights.len());
+
+        SolverProgress {
+            solutions_found: self.solutions_found,
+            conflicts: None,
+            elapsed_ms: self.started_at.elapsed().as_millis() as u64,
+            searched: self.root_weights[..swept].iter().sum(),
+            search_space: self.root_weights.iter().sum(),
+        }
+    }
+
+    /**
+     * Skips this solution if it comes up later
+     */
+    pub fn block_solution(&mut self, vertices: &[usize]) {
+        self.blocked.insert(TeamMask::from_iter(vertices));
+    }
+
+    /**
+     * Without other constraints this only depends on the components:
+     * a vertex is in some subgraph iff its component is big enough,
+     * and in every subgraph iff removing it leaves no component big enough
+     */
+    pub fn backbone(&self) -> Option<Backbone> {
+        let k = self.subgraph_size;
+        let sizes = self.component_sizes(None);
+        if k == 0 || sizes.iter().all(|size| *size < k) {
+            return None;
+        }
+
+        let mut backbone = Backbone::default();
+        for (v, size) in sizes.iter().enumerate() {
+            if *size < k {
+                backbone.forced_out.push(v);
+            } else if self.component_sizes(Some(v)).iter().all(|s| *s < k) {
+                backbone.forced_in.push(v);
+            }
+        }
+
+        Some(backbone)
+    }
+
+    /**
+     * Size of the component of each vertex, 0 for the removed vertex
+     */
+    fn component_sizes(&self, removed: Option<usize>) -> Vec<usize> {
+        let num_vertices = self.neighbours.len();
+        let mut sizes = vec![0; num_vertices];
+        let mut seen = TeamMask::new();
+        if let Some(v) = removed {
+            seen.insert(v);
+        }
+
+        for start in 0..num_vertices {
+            if seen.contains(start) {
+                continue;
+            }
+
+            seen.insert(start);
+            let mut component = vec![start];
+            let mut idx = 0;
+            while idx < component.len() {
+                for u in self.neighbours[component[idx]].iter() {
+                    if !seen.contains(u) {
+                        seen.insert(u);
+                        component.push(u);
+                    }
+                }
+                idx += 1;
+            }
+
+            for v in component.iter() {
+                sizes[*v] = component.len();
+            }
+        }
+
+        sizes
+    }
+
+    /**
+     * Same slices as SubgraphSolver::partition,
+     * ie only the roots v with v % count == index are swept
+     */
+    pub fn partition(&mut self, index: usize, count: usize) {
+        debug_assert!(index < count);
+
+        for (v, weight) in self.root_weights.iter_mut().enumerate() {
+            if v % count != index {
+                *weight = 0.0;
+            }
+        }
+    }
+}
+
+// next() only exists here (and in SubgraphSolver's impl),
+// since an inherent next() reads like Iterator::next
+// and implementing Iterator would shadow partition()
+impl SubgraphSearch for SubgraphEnumerator {
+    fn next(&mut self) -> Option<Solution> {
+        if self.subgraph_size == 0 {
+            return None;
+        }
+
+        loop {
+            let Some(extension) = self.stack.last_mut() else {
+                if self.is_sweeping {
+                    self.is_sweeping = false;
+                    self.root += 1;
+                }
+
+                if self.root >= self.neighbours.len() {
+                    return None;
+                }
+
+                // No subgraph has this root (or it's outside this partition)
+                if self.root_weights[self.root] == 0.0 {
+                    self.root += 1;
+                    continue;
+                }
+
+                self.start_sweep();
+                continue;
+            };
+
+            if extension.subgraph.len() == self.subgraph_size {
+                let mut solution = self.stack.pop().unwrap().subgraph;
+                solution.sort();
+
+                if self.blocked.contains(&TeamMask::from_iter(&solution)) {
+                    continue;
+                }
+
+                self.solutions_found += 1;
+                return Some(solution);
+            }
+
+            let Some(w) = extension.candidates.pop() else {
+                self.stack.pop();
+                continue;
+            };
+
+            // Only w offers its neighbours that no earlier vertex did
+            let mut candidates = extension.candidates.clone();
+            candidates.extend(
+                self.neighbours[w]
+                    .iter()
+                    .filter(|u|
+                        *u > self.root && !extension.neighbourhood.contains(*u)
+                    )
+            );
+
+            let mut subgraph = extension.subgraph.clone();
+            subgraph.push(w);
+
+            let neighbourhood = extension.neighbourhood.union(
+                &self.neighbours[w]
+            );
+
+            self.stack.push(Extension {
+                subgraph,
+                neighbourhood,
+                candidates,
+            });
+        }
+    }
+
+    fn block_solution(&mut self, vertices: &[usize]) {
+        SubgraphEnumerator::block_solution(self, vertices)
+    }
+
+    fn partition(&mut self, index: usize, count: usize) {
+        SubgraphEnumerator::partition(self, index, count)
+    }
+
+    fn progress(&self) -> SolverProgress {
+        SubgraphEnumerator::progress(self)
+    }
+
+    fn backbone(&self) -> Option<Backbone> {
+        SubgraphEnumerator::backbone(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::sat::{
+        build_ab_graph,
+        build_kite_graph,
+        build_square_graph,
+        build_subgraph_contraints,
+        ConnectivityEncoding,
+        Solution,
+        SubgraphSolver,
+    };
+
+    use super::{ SubgraphEnumerator, SubgraphSearch };
+
+    fn collect(search: &mut dyn SubgraphSearch) -> Vec<Solution> {
+        let mut results = Vec::from_iter(std::iter::from_fn(|| search.next()));
+        results.sort();
+
+        results
+    }
+
+    fn fixture_edges(num_vertices: usize) -> HashSet<(i32, i32)> {
+        match num_vertices {
+            2 => HashSet::from_iter([(0, 1)]),
+            4 => HashSet::from_iter([(0, 1), (0, 2), (1, 3), (2, 3)]),
+            _ => HashSet::from_iter([(0, 1), (0, 2), (1, 3), (2, 3), (3, 4)]),
+        }
+    }
+
+    #[test]
+    fn test_fixtures() {
+        for size in 1..=2 {
+            assert_eq!(
+                collect(&mut SubgraphEnumerator::new(2, size, &fixture_edges(2))),
+                collect(&mut SubgraphSolver::new(build_ab_graph(size as i32)))
+            );
+        }
+
+        for size in 1..=4 {
+            assert_eq!(
+                collect(&mut SubgraphEnumerator::new(4, size, &fixture_edges(4))),
+                collect(&mut SubgraphSolver::new(build_square_graph(size as i32)))
+            );
+        }
+
+        for size in 1..=5 {
+            assert_eq!(
+                collect(&mut SubgraphEnumerator::new(5, size, &fixture_edges(5))),
+                collect(&mut SubgraphSolver::new(build_kite_graph(size as i32))),
+                "kite {}",
+                size
+            );
+        }
+    }
+
+    /**
+     * xorshift64, so the graphs are the same on every run
+     */
+    fn random_graph(
+        seed: u64,
+        num_vertices: usize,
+        density: f64
+    ) -> HashSet<(i32, i32)> {
+        let mut state = seed.max(1);
+        let mut random = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state % 1_000_000) as f64 / 1_000_000.0
+        };
+
+        let mut edges = HashSet::new();
+        for i in 0..num_vertices {
+            for j in i + 1..num_vertices {
+                if random() < density {
+                    edges.insert((i as i32, j as i32));
+                }
+            }
+        }
+
+        edges
+    }
+
+    #[test]
+    fn test_random_graphs() {
+        for seed in 1..=30 {
+            let num_vertices = 4 + (seed as usize % 6);
+            let density = 0.15 + (seed % 5) as f64 * 0.15;
+            let edges = random_graph(seed, num_vertices, density);
+
+            for size in 1..=num_vertices.min(5) {
+                let expected = collect(
+                    &mut SubgraphSolver::new(
+                        build_subgraph_contraints(
+                            num_vertices as i32,
+                            size as i32,
+                            edges.clone(),
+                            ConnectivityEncoding::Sparse
+                        )
+                    )
+                );
+                let actual = collect(
+                    &mut SubgraphEnumerator::new(num_vertices, size, &edges)
+                );
+
+                assert_eq!(
+                    actual,
+                    expected,
+                    "seed {}, size {}, edges {:?}",
+                    seed,
+                    size,
+                    edges
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_backbone() {
+        for seed in 1..=30 {
+            let num_vertices = 4 + (seed as usize % 6);
+            let density = 0.1 + (seed % 4) as f64 * 0.1;
+            let edges = random_graph(seed, num_vertices, density);
+
+            for size in 1..=num_vertices.min(5) {
+                let search = SubgraphEnumerator::new(num_vertices, size, &edges);
+                let solver = SubgraphSolver::new(
+                    build_subgraph_contraints(
+                        num_vertices as i32,
+                        size as i32,
+                        edges.clone(),
+                        ConnectivityEncoding::Sparse
+                    )
+                );
+
+                assert_eq!(
+                    SubgraphSearch::backbone(&search),
+                    SubgraphSearch::backbone(&solver),
+                    "seed {}, size {}, edges {:?}",
+                    seed,
+                    size,
+                    edges
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_partition_and_progress() {
+        let edges = fixture_edges(5);
+        let expected = collect(&mut SubgraphEnumerator::new(5, 3, &edges));
+
+        let mut actual = vec![];
+        for index in 0..3 {
+            let mut search = SubgraphEnumerator::new(5, 3, &edges);
+            search.partition(index, 3);
+
+            for sol in collect(&mut search) {
+                assert_eq!(sol[0] % 3, index);
+                actual.push(sol);
+            }
+        }
+        actual.sort();
+        assert_eq!(actual, expected);
+
+        // Same estimate as SubgraphSolver, see solve.rs test_progress
+        let mut search = SubgraphEnumerator::new(5, 3, &edges);
+        assert_eq!(search.progress().search_space, 10.0);
+        for _ in 0..3 {
+            assert_eq!(search.next().unwrap()[0], 0);
+        }
+        search.next();
+        assert_eq!(search.progress().searched, 6.0);
+
+        collect(&mut search);
+        let progress = search.progress();
+        assert_eq!(progress.solutions_found, 6);
+        assert_eq!(progress.fraction_searched(), 1.0);
+    }
+
+    #[test]
+    fn test_block_solution() {
+        let mut search = SubgraphEnumerator::new(5, 3, &fixture_edges(5));
+        search.block_solution(&[3, 1, 0]);
+
+        let solutions = collect(&mut search);
+        assert_eq!(solutions.len(), 5);
+        assert!(!solutions.contains(&vec![0, 1, 3]));
+    }
+}
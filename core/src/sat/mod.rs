@@ -0,0 +1,33 @@
+mod utils;
+mod build_subgraph_constraints;
+mod solve;
+mod build_champion_constraints;
+mod dimacs;
+mod backend;
+mod cdcl;
+mod variables;
+mod prune;
+mod stats;
+mod preflight;
+mod enumerate;
+mod problem;
+mod backbone;
+#[cfg(not(target_arch = "wasm32"))]
+mod parallel;
+
+pub use build_subgraph_constraints::*;
+pub use solve::*;
+pub use utils::*;
+pub use build_champion_constraints::*;
+pub use dimacs::*;
+pub use backend::*;
+pub use cdcl::*;
+pub use variables::*;
+pub use prune::*;
+pub use stats::*;
+pub use preflight::*;
+pub use enumerate::*;
+pub use problem::*;
+pub use backbone::*;
+#[cfg(not(target_arch = "wasm32"))]
+pub use parallel::*;
@@ -0,0 +1,66 @@
+use rayon::prelude::*;
+
+use super::{ Solution, SubgraphSearch, SubgraphSolver };
+
+/**
+ * Enumerates every solution with one solver per slice of the search space
+ * (see SubgraphSolver::partition), running the slices on rayon's thread pool
+ *
+ * Each slice builds its own solver since the formula factory can't be shared between threads.
+ * Using more partitions than threads helps when some slices are much larger than others.
+ */
+pub fn enumerate_parallel<F>(
+    build_solver: F,
+    num_partitions: usize
+) -> Vec<Solution>
+    where F: Fn() -> SubgraphSolver + Sync
+{
+    (0..num_partitions)
+        .into_par_iter()
+        .flat_map_iter(|index| {
+            let mut solver = build_solver();
+            solver.partition(index, num_partitions);
+
+            std::iter::from_fn(move || solver.next())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sat::{
+        build_kite_graph,
+        build_square_graph,
+        SubgraphSearch,
+        SubgraphSolver,
+    };
+
+    use super::enumerate_parallel;
+
+    #[test]
+    fn test_matches_sequential() {
+        for size in 1..=4 {
+            let mut expected: Vec<_> = {
+                let mut solver = SubgraphSolver::new(build_kite_graph(size));
+                std::iter::from_fn(|| solver.next()).collect()
+            };
+            expected.sort();
+
+            for num_partitions in [1, 2, 7] {
+                let mut actual = enumerate_parallel(
+                    || SubgraphSolver::new(build_kite_graph(size)),
+                    num_partitions
+                );
+                actual.sort();
+
+                assert_eq!(actual, expected);
+            }
+        }
+
+        let actual = enumerate_parallel(
+            || SubgraphSolver::new(build_square_graph(2)),
+            4
+        );
+        assert_eq!(actual.len(), 4);
+    }
+}
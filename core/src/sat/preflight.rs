@@ -0,0 +1,416 @@
+use std::ops::{ Add, AddAssign };
+
+use serde::Serialize;
+
+use super::{ is_universal_slot, ConnectivityEncoding, ConstraintFamily };
+
+/**
+ * Approximate size of the CNF an encoding converts to,
+ * computed from the shape of the graph without building any formulas
+ *
+ * Counts the same way as ConstraintStats,
+ * but assumes every exactly-one / at-most-one constraint is encoded the way
+ * LogicNG does by default (pairwise up to 10 variables, product encoding above)
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct SizeEstimate {
+    pub variables: usize,
+    pub clauses: usize,
+}
+
+impl Add for SizeEstimate {
+    type Output = SizeEstimate;
+
+    fn add(self, other: SizeEstimate) -> SizeEstimate {
+        SizeEstimate {
+            variables: self.variables + other.variables,
+            clauses: self.clauses + other.clauses,
+        }
+    }
+}
+
+impl AddAssign for SizeEstimate {
+    fn add_assign(&mut self, other: SizeEstimate) {
+        *self = *self + other;
+    }
+}
+
+/**
+ * The estimated constraints exceed the ceiling,
+ * even with the cheapest encoding
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub struct SearchTooLarge {
+    pub estimate: SizeEstimate,
+    pub max_clauses: usize,
+}
+
+/**
+ * Mirrors build_subgraph_contraints
+ *
+ * Doesn't depend on the edges, since the sparse encoding
+ * has one (longer or shorter) clause per vertex and timestep either way
+ */
+pub fn estimate_subgraph_constraints(
+    num_vertices: usize,
+    subgraph_size: usize,
+    encoding: ConnectivityEncoding
+) -> Vec<(ConstraintFamily, SizeEstimate)> {
+    let n = num_vertices;
+    let k = subgraph_size;
+
+    let exactly_one = at_most_one(n) + SizeEstimate {
+        variables: 0,
+        clauses: 1,
+    };
+    let decisions = SizeEstimate {
+        // Vertex and decision variables
+        variables: n + k * n + k * exactly_one.variables,
+        // Non-empty, decision => vertex, vertex => any decision
+        clauses: 1 + k * n + n + k * exactly_one.clauses,
+    } + exactly_k(n, k);
+
+    let no_repeat = SizeEstimate {
+        variables: 0,
+        clauses: (n * k * k.saturating_sub(1)) / 2,
+    };
+
+    let (connectivity, edges) = match encoding {
+        ConnectivityEncoding::Dense => {
+            // Each (earlier decision, edge) pair gets an auxiliary variable
+            // defined by 2 clauses, shared between later timesteps
+            let num_aux = k.saturating_sub(1) * n * n;
+
+            (
+                SizeEstimate {
+                    variables: num_aux,
+                    clauses: k.saturating_sub(1) * n + 2 * num_aux,
+                },
+                // Undirected + fixed to the graph
+                SizeEstimate {
+                    variables: n * n,
+                    clauses: n * n.saturating_sub(1) + n * n,
+                },
+            )
+        }
+        ConnectivityEncoding::Sparse => {
+            (
+                SizeEstimate {
+                    variables: 0,
+                    clauses: k.saturating_sub(1) * n,
+                },
+                SizeEstimate::default(),
+            )
+        }
+    };
+
+    vec![
+        (ConstraintFamily::Decisions, decisions),
+        (ConstraintFamily::Connectivity, connectivity),
+        (ConstraintFamily::NoRepeat, no_repeat),
+        (ConstraintFamily::Edges, edges)
+    ]
+}
+
+/**
+ * Mirrors build_slot_constraints, for slots that have already been
+ * renumbered to the vertices of the graph
+ */
+pub fn estimate_slot_constraints(
+    num_vertices: usize,
+    subgraph_size: usize,
+    slot_options: &[Vec<usize>]
+) -> Vec<(ConstraintFamily, SizeEstimate)> {
+    let mut num_slots = vec![0; num_vertices];
+    let mut num_constrained = 0;
+    let mut num_options = 0;
+
+    for options in slot_options.iter() {
+        // Skipped by build_slot_constraints
+        if is_universal_slot(
+            options,
+            num_vertices,
+            subgraph_size,
+            slot_options.len()
+        ) {
+            continue;
+        }
+
+        num_constrained += 1;
+        num_options += options.len();
+        for v in options.iter() {
+            num_slots[*v] += 1;
+        }
+    }
+
+    let coverage = SizeEstimate {
+        variables: num_options,
+        // One clause per slot, plus an implication per assignment
+        clauses: num_constrained + num_options,
+    };
+
+    let mut dedupe = SizeEstimate::default();
+    for count in num_slots {
+        dedupe += at_most_one(count);
+    }
+
+    vec![
+        (ConstraintFamily::SlotCoverage, coverage),
+        (ConstraintFamily::SlotDedupe, dedupe)
+    ]
+}
+
+/**
+ * Mirrors build_degree_constraints, given the number of neighbours of each vertex
+ */
+pub fn estimate_degree_constraints(
+    degrees: &[usize],
+    min_degree: usize
+) -> Vec<(ConstraintFamily, SizeEstimate)> {
+    let m = min_degree;
+    let mut estimate = SizeEstimate::default();
+
+    for d in degrees.iter().copied() {
+        if m == 0 {
+            break;
+        }
+
+        // Unit clause, or one clause over the neighbours
+        if d < m || m == 1 {
+            estimate.clauses += 1;
+            continue;
+        }
+
+        for j in 1..=d {
+            let num_counters = j.min(m);
+
+            // One clause per counter, plus one for each with r >= 2
+            estimate.variables += num_counters;
+            estimate.clauses += 2 * num_counters - 1;
+        }
+        estimate.clauses += 1;
+    }
+
+    vec![(ConstraintFamily::MinDegree, estimate)]
+}
+
+/**
+ * Sum over the families that aren't disabled
+ */
+pub fn total_estimate(
+    estimates: &[(ConstraintFamily, SizeEstimate)],
+    disabled: &[ConstraintFamily]
+) -> SizeEstimate {
+    estimates
+        .iter()
+        .filter(|(family, _)| !disabled.contains(family))
+        .fold(SizeEstimate::default(), |total, (_, estimate)| {
+            total + *estimate
+        })
+}
+
+/**
+ * Exactly-k constraints with 1 < k < num_vars are encoded as a totalizer,
+ * a tree of unary adders with an output per count
+ */
+fn exactly_k(num_vars: usize, k: usize) -> SizeEstimate {
+    let n = num_vars;
+
+    if k > n {
+        SizeEstimate { variables: 0, clauses: 1 }
+    } else if k == 0 || k == n {
+        SizeEstimate { variables: 0, clauses: n }
+    } else if k == 1 {
+        at_most_one(n) + SizeEstimate { variables: 0, clauses: 1 }
+    } else {
+        // Outputs of the root, each fixed by a unit clause
+        SizeEstimate { variables: n, clauses: n } + totalizer(n, k)
+    }
+}
+
+/**
+ * Adders below a node of the totalizer with `num_outputs` outputs,
+ * leaves are the input variables themselves
+ */
+fn totalizer(num_outputs: usize, k: usize) -> SizeEstimate {
+    let left = num_outputs / 2;
+    let right = num_outputs - left;
+    let mut estimate = SizeEstimate::default();
+
+    for side in [left, right] {
+        if side > 1 {
+            estimate.variables += side;
+            estimate += totalizer(side, k);
+        }
+    }
+
+    for i in 0..=left {
+        for j in 0..=right {
+            if i == 0 && j == 0 {
+                continue;
+            }
+
+            // Upper bound, only up to k + 1
+            if i + j <= k + 1 {
+                estimate.clauses += 1;
+            }
+            // Lower bound
+            estimate.clauses += 1;
+        }
+    }
+
+    estimate
+}
+
+fn at_most_one(num_vars: usize) -> SizeEstimate {
+    if num_vars <= 1 {
+        SizeEstimate::default()
+    } else if num_vars <= 10 {
+        SizeEstimate {
+            variables: 0,
+            clauses: (num_vars * (num_vars - 1)) / 2,
+        }
+    } else {
+        // Vertices of a p x q grid, with an at-most-one over each side
+        let p = (num_vars as f64).sqrt().ceil() as usize;
+        let q = num_vars.div_ceil(p);
+        let sides = at_most_one(p) + at_most_one(q);
+
+        SizeEstimate {
+            variables: p + q + sides.variables,
+            clauses: 2 * num_vars + sides.clauses,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::sat::{
+        build_degree_constraints,
+        build_kite_graph_with,
+        neighbour_lists,
+        ConnectivityEncoding,
+        ConstraintFamily,
+    };
+
+    use super::{
+        estimate_degree_constraints,
+        estimate_subgraph_constraints,
+        total_estimate,
+    };
+
+    #[test]
+    fn test_kite() {
+        for encoding in [
+            ConnectivityEncoding::Dense,
+            ConnectivityEncoding::Sparse,
+        ] {
+            let constraints = build_kite_graph_with(3, encoding);
+            let stats = constraints.stats();
+            let estimates = estimate_subgraph_constraints(
+                5,
+                3,
+                encoding
+            );
+
+            // Exact for everything but the exactly-one constraints
+            for (family, estimate) in estimates.iter() {
+                let actual = stats.families
+                    .iter()
+                    .find(|s| s.family == *family)
+                    .map_or(0, |s| s.clauses);
+
+                if *family != ConstraintFamily::Decisions {
+                    assert_eq!(estimate.clauses, actual, "{}", family);
+                }
+            }
+
+            let total = total_estimate(&estimates, &[]);
+            assert_eq!(total.clauses, stats.clauses);
+            assert_eq!(total.variables, stats.variables);
+        }
+
+        // Decisions are the same for both encodings
+        let dense = total_estimate(
+            &estimate_subgraph_constraints(
+                60,
+                9,
+                ConnectivityEncoding::Dense
+            ),
+            &[ConstraintFamily::Decisions]
+        );
+        let sparse = total_estimate(
+            &estimate_subgraph_constraints(
+                60,
+                9,
+                ConnectivityEncoding::Sparse
+            ),
+            &[ConstraintFamily::Decisions]
+        );
+        assert!(sparse.clauses * 10 < dense.clauses);
+
+        // Disabled families aren't counted
+        let estimates = estimate_subgraph_constraints(
+            5,
+            3,
+            ConnectivityEncoding::Dense
+        );
+        let families = HashSet::<ConstraintFamily>::from_iter(
+            estimates.iter().map(|(family, _)| *family)
+        );
+        assert_eq!(
+            total_estimate(&estimates, &Vec::from_iter(families)),
+            Default::default()
+        );
+    }
+
+    #[test]
+    fn test_degree() {
+        let edges = HashSet::from_iter([
+            (0, 1),
+            (0, 2),
+            (1, 3),
+            (2, 3),
+            (3, 4),
+        ]);
+        let degrees = Vec::from_iter(
+            neighbour_lists(5, &edges)
+                .iter()
+                .map(|ns| ns.len())
+        );
+
+        for min_degree in 0..=4 {
+            let mut constraints = build_kite_graph_with(
+                3,
+                ConnectivityEncoding::Sparse
+            );
+            let before = constraints.stats();
+
+            build_degree_constraints(
+                min_degree,
+                &edges,
+                &mut constraints
+            );
+            let after = constraints.stats();
+
+            let estimate = total_estimate(
+                &estimate_degree_constraints(&degrees, min_degree),
+                &[]
+            );
+            assert_eq!(
+                estimate.clauses,
+                after.clauses - before.clauses,
+                "min degree {}",
+                min_degree
+            );
+            assert_eq!(
+                estimate.variables,
+                after.variables - before.variables,
+                "min degree {}",
+                min_degree
+            );
+        }
+    }
+}
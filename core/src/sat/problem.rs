@@ -0,0 +1,538 @@
+use std::collections::{ HashMap, HashSet };
+use std::fmt;
+use std::hash::Hash;
+use std::ops::RangeInclusive;
+
+use logicng::formulas::{ CType, Literal };
+
+use super::{
+    build_degree_constraints,
+    build_slot_constraints,
+    build_subgraph_contraints,
+    estimate_degree_constraints,
+    estimate_slot_constraints,
+    estimate_subgraph_constraints,
+    neighbour_lists,
+    total_estimate,
+    ConnectivityEncoding,
+    Constraint,
+    ConstraintFamily,
+    MiniSatBackend,
+    SizeEstimate,
+    SubgraphConstraints,
+    SubgraphSearch,
+    SubgraphSolver,
+    TeamSolverBackend,
+};
+
+/**
+ * Enumerates the connected subgraphs of a graph with side constraints,
+ * independent of champions and traits
+ *
+ * Vertices can be any hashable key, eg champion ids or indices,
+ * and are numbered in the order they were passed in.
+ * Every subgraph has a size in `sizes`,
+ * smaller subgraphs are enumerated before larger ones.
+ *
+ * ```
+ * use tft_core::sat::{ ConnectedSubgraphProblem, ProblemError };
+ *
+ * let mut problem = ConnectedSubgraphProblem::new(
+ *     ["a", "b", "c"],
+ *     [("a", "b"), ("b", "c")],
+ *     2..=3
+ * )?;
+ * problem.exclude(&"c")?;
+ *
+ * let subgraphs = Vec::from_iter(problem.solutions());
+ * assert_eq!(subgraphs, vec![vec!["a", "b"]]);
+ * # Ok::<(), ProblemError>(())
+ * ```
+ */
+#[derive(Clone, Debug)]
+pub struct ConnectedSubgraphProblem<V> {
+    vertices: Vec<V>,
+    indices: HashMap<V, usize>,
+    edges: HashSet<(i32, i32)>,
+    sizes: RangeInclusive<usize>,
+
+    // Each picked vertex must share an edge with this many other picked vertices
+    // (0 for no minimum beyond connectivity)
+    pub min_degree: usize,
+    pub encoding: ConnectivityEncoding,
+    // Constraint families to leave out
+    pub disabled: Vec<ConstraintFamily>,
+
+    // Vertex indices of the options for each slot
+    slots: Vec<Vec<usize>>,
+    side_constraints: Vec<SideConstraint>,
+}
+
+/**
+ * Over vertex indices, see the methods of ConnectedSubgraphProblem that add them
+ */
+#[derive(Clone, Debug, PartialEq)]
+enum SideConstraint {
+    // (vertex, whether it's picked)
+    Clause(Vec<(usize, bool)>),
+    AtLeast(usize, Vec<usize>),
+    AtMost(usize, Vec<usize>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ProblemError {
+    // Debug representation of the vertex
+    DuplicateVertex(String),
+    UnknownVertex(String),
+}
+
+impl fmt::Display for ProblemError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DuplicateVertex(v) => write!(f, "Vertex {} appears twice", v),
+            Self::UnknownVertex(v) => write!(f, "Vertex {} is not in the graph", v),
+        }
+    }
+}
+
+impl<V> ConnectedSubgraphProblem<V>
+    where V: Clone + Eq + Hash + fmt::Debug
+{
+    /**
+     * Edges are undirected, loops are ignored
+     */
+    pub fn new(
+        vertices: impl IntoIterator<Item = V>,
+        edges: impl IntoIterator<Item = (V, V)>,
+        sizes: RangeInclusive<usize>
+    ) -> Result<Self, ProblemError> {
+        let vertices = Vec::from_iter(vertices);
+
+        let mut indices = HashMap::with_capacity(vertices.len());
+        for (idx, v) in vertices.iter().enumerate() {
+            if indices.insert(v.clone(), idx).is_some() {
+                return Err(ProblemError::DuplicateVertex(format!("{:?}", v)));
+            }
+        }
+
+        let mut problem = Self {
+            vertices,
+            indices,
+            edges: HashSet::new(),
+            sizes,
+            min_degree: 0,
+            encoding: ConnectivityEncoding::Dense,
+            disabled: vec![],
+            slots: vec![],
+            side_constraints: vec![],
+        };
+
+        for (a, b) in edges {
+            let (a, b) = (problem.index(&a)?, problem.index(&b)?);
+            if a != b {
+                problem.edges.insert((a.min(b) as i32, a.max(b) as i32));
+            }
+        }
+
+        Ok(problem)
+    }
+
+    pub fn vertices(&self) -> &[V] {
+        &self.vertices
+    }
+
+    /**
+     * Each edge once, in no particular order
+     * (the vertex that was passed in first comes first)
+     */
+    pub fn edges(&self) -> impl Iterator<Item = (&V, &V)> + '_ {
+        self.edges
+            .iter()
+            .map(|(a, b)| (
+                &self.vertices[*a as usize],
+                &self.vertices[*b as usize],
+            ))
+    }
+
+    pub fn sizes(&self) -> &RangeInclusive<usize> {
+        &self.sizes
+    }
+
+    pub fn index_of(&self, vertex: &V) -> Option<usize> {
+        self.indices.get(vertex).copied()
+    }
+
+    fn index(&self, vertex: &V) -> Result<usize, ProblemError> {
+        self.index_of(vertex).ok_or_else(||
+            ProblemError::UnknownVertex(format!("{:?}", vertex))
+        )
+    }
+
+    fn indices_of(&self, vertices: &[V]) -> Result<Vec<usize>, ProblemError> {
+        vertices
+            .iter()
+            .map(|v| self.index(v))
+            .collect()
+    }
+
+    /**
+     * Each slot must be filled by a different vertex of the subgraph,
+     * so there are no subgraphs smaller than the number of slots
+     * (solutions() skips those sizes)
+     */
+    pub fn add_slot(&mut self, options: &[V]) -> Result<(), ProblemError> {
+        let mut options = self.indices_of(options)?;
+        options.sort();
+        options.dedup();
+
+        self.slots.push(options);
+        Ok(())
+    }
+
+    pub fn include(&mut self, vertex: &V) -> Result<(), ProblemError> {
+        self.add_clause(&[(vertex.clone(), true)])
+    }
+
+    pub fn exclude(&mut self, vertex: &V) -> Result<(), ProblemError> {
+        self.add_clause(&[(vertex.clone(), false)])
+    }
+
+    /**
+     * At least one of the literals holds,
+     * where (v, true) means v is picked and (v, false) that it isn't
+     */
+    pub fn add_clause(
+        &mut self,
+        literals: &[(V, bool)]
+    ) -> Result<(), ProblemError> {
+        let literals = literals
+            .iter()
+            .map(|(v, phase)| Ok((self.index(v)?, *phase)))
+            .collect::<Result<Vec<_>, ProblemError>>()?;
+
+        self.side_constraints.push(SideConstraint::Clause(literals));
+        Ok(())
+    }
+
+    pub fn at_least(
+        &mut self,
+        count: usize,
+        vertices: &[V]
+    ) -> Result<(), ProblemError> {
+        let vertices = self.indices_of(vertices)?;
+        self.side_constraints.push(SideConstraint::AtLeast(count, vertices));
+        Ok(())
+    }
+
+    pub fn at_most(
+        &mut self,
+        count: usize,
+        vertices: &[V]
+    ) -> Result<(), ProblemError> {
+        let vertices = self.indices_of(vertices)?;
+        self.side_constraints.push(SideConstraint::AtMost(count, vertices));
+        Ok(())
+    }
+
+    /**
+     * Size of the formula for subgraphs of one size, see preflight.rs
+     *
+     * Side constraints are counted as one clause each
+     */
+    pub fn estimate(&self, subgraph_size: usize) -> SizeEstimate {
+        let num_vertices = self.vertices.len();
+        let degrees = Vec::from_iter(
+            neighbour_lists(num_vertices, &self.edges)
+                .iter()
+                .map(|ns| ns.len())
+        );
+
+        let mut estimates = estimate_subgraph_constraints(
+            num_vertices,
+            subgraph_size,
+            self.encoding
+        );
+        estimates.extend(
+            estimate_slot_constraints(num_vertices, subgraph_size, &self.slots)
+        );
+        estimates.extend(estimate_degree_constraints(&degrees, self.min_degree));
+        estimates.push((
+            ConstraintFamily::Custom,
+            SizeEstimate {
+                variables: 0,
+                clauses: self.side_constraints.len(),
+            },
+        ));
+
+        total_estimate(&estimates, &self.disabled)
+    }
+
+    /**
+     * Formula for the subgraphs of one size,
+     * vertex i of the constraints is vertices()[i]
+     */
+    pub fn build(&self, subgraph_size: usize) -> SubgraphConstraints {
+        let mut constraints = build_subgraph_contraints(
+            self.vertices.len() as i32,
+            subgraph_size as i32,
+            self.edges.clone(),
+            self.encoding
+        );
+
+        build_degree_constraints(
+            self.min_degree,
+            &self.edges,
+            &mut constraints
+        );
+        build_slot_constraints(&self.slots, &mut constraints);
+
+        let side_constraints = self.build_side_constraints(&constraints);
+        constraints.extend(side_constraints);
+
+        constraints.disable(&self.disabled);
+
+        constraints
+    }
+
+    fn build_side_constraints(
+        &self,
+        subgraph_constraints: &SubgraphConstraints
+    ) -> Vec<(ConstraintFamily, Constraint)> {
+        let f = &subgraph_constraints.factory;
+        let variables = &subgraph_constraints.variables;
+
+        Vec::from_iter(
+            self.side_constraints.iter().map(|side_constraint| {
+                let constraint = match side_constraint {
+                    SideConstraint::Clause(literals) => {
+                        let literals = Vec::from_iter(
+                            literals
+                                .iter()
+                                .map(|(v, phase)|
+                                    Literal::new(variables.vertex(*v), *phase)
+                                )
+                        );
+
+                        Constraint::Clause(literals)
+                    }
+                    SideConstraint::AtLeast(count, vertices) => {
+                        Constraint::Formula(f.cc(
+                            CType::GE,
+                            *count as u64,
+                            Vec::from_iter(
+                                vertices.iter().map(|v| variables.vertex(*v))
+                            )
+                        ))
+                    }
+                    SideConstraint::AtMost(count, vertices) => {
+                        Constraint::Formula(f.cc(
+                            CType::LE,
+                            *count as u64,
+                            Vec::from_iter(
+                                vertices.iter().map(|v| variables.vertex(*v))
+                            )
+                        ))
+                    }
+                };
+
+                (ConstraintFamily::Custom, constraint)
+            })
+        )
+    }
+
+    /**
+     * Every subgraph matching the constraints, solved with MiniSat
+     */
+    pub fn solutions(&self) -> ProblemSolutions<'_, V> {
+        self.solutions_with(|| Box::new(MiniSatBackend::new()))
+    }
+
+    /**
+     * Every subgraph matching the constraints,
+     * with a new backend for each size
+     */
+    pub fn solutions_with<B>(&self, build_backend: B) -> ProblemSolutions<'_, V>
+        where B: Fn() -> Box<dyn TeamSolverBackend> + 'static
+    {
+        ProblemSolutions {
+            problem: self,
+            build_backend: Box::new(build_backend),
+            next_size: *self.sizes.start(),
+            solver: None,
+        }
+    }
+}
+
+/**
+ * Lazily builds a solver for each size once the previous one runs out
+ */
+pub struct ProblemSolutions<'a, V> {
+    problem: &'a ConnectedSubgraphProblem<V>,
+    build_backend: Box<dyn Fn() -> Box<dyn TeamSolverBackend>>,
+    next_size: usize,
+    solver: Option<SubgraphSolver>,
+}
+
+impl<V> Iterator for ProblemSolutions<'_, V>
+    where V: Clone + Eq + Hash + fmt::Debug
+{
+    type Item = Vec<V>;
+
+    fn next(&mut self) -> Option<Vec<V>> {
+        loop {
+            if let Some(solution) = self.solver.as_mut().and_then(|s| s.next()) {
+                return Some(
+                    Vec::from_iter(
+                        solution
+                            .iter()
+                            .map(|v| self.problem.vertices[*v].clone())
+                    )
+                );
+            }
+
+            if self.next_size > *self.problem.sizes.end() {
+                self.solver = None;
+                return None;
+            }
+
+            let size = self.next_size;
+            self.next_size += 1;
+
+            // Subgraphs can't be empty, or have fewer vertices than slots
+            if size == 0 || size < self.problem.slots.len() {
+                continue;
+            }
+
+            self.solver = Some(
+                SubgraphSolver::new_with_backend(
+                    self.problem.build(size),
+                    (self.build_backend)()
+                )
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::{ ConnectedSubgraphProblem, ProblemError };
+
+    /**
+     *   a
+     *  / \
+     * b   c
+     *  \ /
+     *   d
+     *   |
+     *   e
+     */
+    fn kite(
+        sizes: std::ops::RangeInclusive<usize>
+    ) -> ConnectedSubgraphProblem<&'static str> {
+        ConnectedSubgraphProblem::new(
+            ["a", "b", "c", "d", "e"],
+            [("a", "b"), ("a", "c"), ("b", "d"), ("c", "d"), ("d", "e")],
+            sizes
+        ).unwrap()
+    }
+
+    fn collect(
+        problem: &ConnectedSubgraphProblem<&'static str>
+    ) -> HashSet<String> {
+        HashSet::from_iter(problem.solutions().map(|vs| vs.concat()))
+    }
+
+    fn set(subgraphs: &[&str]) -> HashSet<String> {
+        HashSet::from_iter(subgraphs.iter().map(|s| s.to_string()))
+    }
+
+    #[test]
+    fn test_edges() {
+        let problem = ConnectedSubgraphProblem::new(
+            ["a", "b", "c"],
+            [("b", "a"), ("a", "b"), ("c", "c"), ("b", "c")],
+            1..=3
+        ).unwrap();
+
+        let edges = HashSet::<(&str, &str)>::from_iter(
+            problem.edges().map(|(a, b)| (*a, *b))
+        );
+        assert_eq!(edges, HashSet::from_iter([("a", "b"), ("b", "c")]));
+    }
+
+    #[test]
+    fn test_sizes() {
+        let problem = kite(2..=3);
+        let sizes = Vec::from_iter(problem.solutions().map(|vs| vs.len()));
+
+        // 5 edges, then 6 paths of 3 vertices, smallest first
+        assert_eq!(sizes.len(), 11);
+        assert!(sizes.windows(2).all(|w| w[0] <= w[1]));
+
+        assert_eq!(collect(&kite(5..=5)), set(&["abcde"]));
+    }
+
+    #[test]
+    fn test_side_constraints() {
+        let mut problem = kite(3..=3);
+        problem.include(&"d").unwrap();
+        problem.exclude(&"e").unwrap();
+        assert_eq!(collect(&problem), set(&["abd", "acd", "bcd"]));
+
+        problem.at_most(1, &["b", "c"]).unwrap();
+        assert_eq!(collect(&problem), set(&["abd", "acd"]));
+
+        let mut problem = kite(2..=2);
+        problem.at_least(2, &["a", "b", "c"]).unwrap();
+        assert_eq!(collect(&problem), set(&["ab", "ac"]));
+
+        let mut problem = kite(2..=2);
+        problem.add_clause(&[("a", false), ("e", true)]).unwrap();
+        assert_eq!(collect(&problem), set(&["bd", "cd", "de"]));
+    }
+
+    #[test]
+    fn test_slots_and_degree() {
+        let mut problem = kite(3..=3);
+        problem.add_slot(&["a", "e"]).unwrap();
+        problem.add_slot(&["e"]).unwrap();
+        assert_eq!(collect(&problem), set(&[]));
+
+        let mut problem = kite(2..=5);
+        problem.min_degree = 2;
+        assert_eq!(collect(&problem), set(&["abcd"]));
+    }
+
+    #[test]
+    fn test_universal_slot() {
+        let problem = |sizes| {
+            let mut problem = ConnectedSubgraphProblem::new(
+                ["a", "b", "c"],
+                [("a", "b"), ("b", "c")],
+                sizes
+            ).unwrap();
+            problem.add_slot(&["a", "b", "c"]).unwrap();
+            problem.add_slot(&["a"]).unwrap();
+            problem
+        };
+
+        // "a" can't fill both slots on its own
+        assert_eq!(collect(&problem(1..=3)), set(&["ab", "abc"]));
+        assert_eq!(collect(&problem(1..=1)), set(&[]));
+    }
+
+    #[test]
+    fn test_unknown_vertex() {
+        assert_eq!(
+            ConnectedSubgraphProblem::new(["a"], [("a", "b")], 1..=1).err(),
+            Some(ProblemError::UnknownVertex("\"b\"".to_string()))
+        );
+        assert_eq!(
+            ConnectedSubgraphProblem::new(["a", "a"], [], 1..=1).err(),
+            Some(ProblemError::DuplicateVertex("\"a\"".to_string()))
+        );
+        assert!(kite(1..=1).exclude(&"f").is_err());
+    }
+}
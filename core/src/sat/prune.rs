@@ -0,0 +1,174 @@
+use std::collections::HashSet;
+
+use serde::Serialize;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PruneReason {
+    // Every team member fills a slot, but this vertex isn't an option for any slot
+    OutsideSlots,
+    // No edges, so it can only be in a team of one
+    Isolated,
+    // Connected component has fewer vertices than the team size
+    ComponentTooSmall,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct PruneReport {
+    // Vertices that can be in a team, ascending
+    pub kept: Vec<usize>,
+    pub removed: Vec<(usize, PruneReason)>,
+    // Connected components of the kept vertices, each ascending
+    // (any team lies entirely inside one of them)
+    pub components: Vec<Vec<usize>>,
+}
+
+/**
+ * Finds vertices that can't be in any connected subgraph of the given size
+ * so they can be left out of the encoding entirely
+ *
+ * This only looks at the graph and the union of the slot options,
+ * the SAT solver still rules out everything else
+ */
+pub fn prune_graph(
+    num_vertices: usize,
+    edges: &HashSet<(i32, i32)>,
+    subgraph_size: usize,
+    slot_options: &[Vec<usize>]
+) -> PruneReport {
+    let mut report = PruneReport::default();
+    let mut is_candidate = vec![true; num_vertices];
+
+    // When every team member has to fill a slot,
+    // vertices that aren't an option for any slot can't be picked
+    if slot_options.len() >= subgraph_size {
+        let mut in_any_slot = vec![false; num_vertices];
+        for idx in slot_options.iter().flatten() {
+            in_any_slot[*idx] = true;
+        }
+
+        for idx in 0..num_vertices {
+            if !in_any_slot[idx] {
+                is_candidate[idx] = false;
+                report.removed.push((idx, PruneReason::OutsideSlots));
+            }
+        }
+    }
+
+    let mut neighbours = vec![Vec::<usize>::new(); num_vertices];
+    for (a, b) in edges.iter() {
+        let (a, b) = (*a as usize, *b as usize);
+        if a != b && is_candidate[a] && is_candidate[b] {
+            neighbours[a].push(b);
+            neighbours[b].push(a);
+        }
+    }
+
+    // Connected components of the remaining vertices
+    let mut visited = vec![false; num_vertices];
+    for start in 0..num_vertices {
+        if !is_candidate[start] || visited[start] {
+            continue;
+        }
+
+        let mut component = vec![start];
+        visited[start] = true;
+
+        let mut idx = 0;
+        while idx < component.len() {
+            for next in neighbours[component[idx]].iter() {
+                if !visited[*next] {
+                    visited[*next] = true;
+                    component.push(*next);
+                }
+            }
+
+            idx += 1;
+        }
+
+        if component.len() < subgraph_size {
+            let reason = if component.len() == 1 {
+                PruneReason::Isolated
+            } else {
+                PruneReason::ComponentTooSmall
+            };
+
+            for v in component {
+                is_candidate[v] = false;
+                report.removed.push((v, reason));
+            }
+        } else {
+            component.sort();
+            report.components.push(component);
+        }
+    }
+
+    report.kept = (0..num_vertices).filter(|v| is_candidate[*v]).collect();
+    report.removed.sort_by_key(|(v, _)| *v);
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::{ prune_graph, PruneReason };
+
+    /**
+     *   0 - 1 - 2    3 - 4    5
+     */
+    fn edges() -> HashSet<(i32, i32)> {
+        HashSet::from_iter([(0, 1), (1, 2), (3, 4)])
+    }
+
+    #[test]
+    fn test_components() {
+        let report = prune_graph(6, &edges(), 3, &[]);
+
+        assert_eq!(report.kept, vec![0, 1, 2]);
+        assert_eq!(report.components, vec![vec![0, 1, 2]]);
+        assert_eq!(
+            report.removed,
+            vec![
+                (3, PruneReason::ComponentTooSmall),
+                (4, PruneReason::ComponentTooSmall),
+                (5, PruneReason::Isolated),
+            ]
+        );
+
+        // Teams of one can use any vertex
+        let report = prune_graph(6, &edges(), 1, &[]);
+        assert_eq!(report.kept, vec![0, 1, 2, 3, 4, 5]);
+        assert!(report.removed.is_empty());
+        assert_eq!(
+            report.components,
+            vec![vec![0, 1, 2], vec![3, 4], vec![5]]
+        );
+    }
+
+    #[test]
+    fn test_slots() {
+        // Without 0, the path 1 - 2 is too short
+        let slots = vec![vec![1, 2], vec![2, 3], vec![4]];
+        let report = prune_graph(6, &edges(), 3, &slots);
+
+        assert_eq!(report.kept, Vec::<usize>::new());
+        assert_eq!(
+            report.removed,
+            vec![
+                (0, PruneReason::OutsideSlots),
+                (1, PruneReason::ComponentTooSmall),
+                (2, PruneReason::ComponentTooSmall),
+                (3, PruneReason::ComponentTooSmall),
+                (4, PruneReason::ComponentTooSmall),
+                (5, PruneReason::OutsideSlots),
+            ]
+        );
+
+        // Unrestricted team members can be anyone
+        let report = prune_graph(6, &edges(), 2, &[vec![1]]);
+        assert_eq!(report.kept, vec![0, 1, 2, 3, 4]);
+        assert_eq!(report.components, vec![vec![0, 1, 2], vec![3, 4]]);
+    }
+}
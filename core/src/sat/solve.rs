@@ -0,0 +1,751 @@
+use logicng::formulas::{ CType, Literal, Variable };
+use serde::Serialize;
+use web_time::Instant;
+
+use super::{
+    vertex_backbone,
+    Backbone,
+    MiniSatBackend,
+    SubgraphConstraints,
+    SubgraphSearch,
+    TeamSolverBackend,
+};
+
+// Indices of the vertices in the subgraph, ascending
+pub type Solution = Vec<usize>;
+
+/**
+ * Reimplements logicng::solver::functions::enumerate_models
+ * to make it lazy / generator-like
+ *
+ * Solutions are enumerated in sweeps over their lowest vertex (the root),
+ * so the roots that were swept tell us how much of the search space is done
+ */
+pub struct SubgraphSolver {
+    pub constraints: SubgraphConstraints,
+    pub backend: Box<dyn TeamSolverBackend>,
+    pub solution_variables: Vec<Variable>,
+
+    // Minimum number of champions each new solution must differ by
+    // from every previous solution (1 = plain model enumeration)
+    pub diversity: usize,
+
+    // Root of the current sweep
+    root: usize,
+    // Number of vertex sets of the subgraph size with each root,
+    // used to estimate the fraction of the search space per sweep
+    root_weights: Vec<f64>,
+    solutions_found: usize,
+    started_at: Instant,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct SolverProgress {
+    pub solutions_found: usize,
+    // None if the backend doesn't count conflicts
+    pub conflicts: Option<u64>,
+    pub elapsed_ms: u64,
+    // Estimated share of the search space that was already enumerated,
+    // out of search_space (both are counts of vertex sets)
+    pub searched: f64,
+    pub search_space: f64,
+}
+
+impl SolverProgress {
+    pub fn fraction_searched(&self) -> f64 {
+        if self.search_space > 0.0 {
+            self.searched / self.search_space
+        } else {
+            1.0
+        }
+    }
+}
+
+impl SubgraphSolver {
+    pub fn new(constraints: SubgraphConstraints) -> Self {
+        Self::new_with_backend(
+            constraints,
+            Box::new(MiniSatBackend::new())
+        )
+    }
+
+    pub fn new_with_backend(
+        constraints: SubgraphConstraints,
+        mut backend: Box<dyn TeamSolverBackend>
+    ) -> Self {
+        constraints.add_to(backend.as_mut());
+
+        let solution_variables = constraints.variables.vertices().to_vec();
+
+        // Choosing the rest of the subgraph from the vertices after the root
+        let num_vertices = solution_variables.len();
+        let subgraph_size = constraints.subgraph_size as usize;
+        let root_weights = Vec::from_iter(
+            (0..num_vertices).map(|root|
+                num_combinations(
+                    num_vertices - root - 1,
+                    subgraph_size.saturating_sub(1)
+                )
+            )
+        );
+
+        Self {
+            constraints,
+            backend,
+            solution_variables,
+            diversity: 1,
+            root: 0,
+            root_weights,
+            solutions_found: 0,
+            started_at: Instant::now(),
+        }
+    }
+
+    /**
+     * Progress estimate based on the sweeps that have finished,
+     * the search space is an upper bound since it ignores the edges
+     */
+    pub fn progress(&self) -> SolverProgress {
+        let swept = self.root.min(self.root_weights.len());
+
+        SolverProgress {
+            solutions_found: self.solutions_found,
+            conflicts: self.backend.num_conflicts(),
+            elapsed_ms: self.started_at.elapsed().as_millis() as u64,
+            searched: self.root_weights[..swept].iter().sum(),
+            search_space: self.root_weights.iter().sum(),
+        }
+    }
+
+    /**
+     * Blocks a solution that was found by a previous solver for the same constraints
+     * (eg when resuming a search), so that it isn't returned again.
+     *
+     * Each vertex is an index into solution_variables
+     */
+    pub fn block_solution(&mut self, vertices: &[usize]) {
+        let vars: Vec<Variable> = vertices
+            .iter()
+            .map(|idx| self.solution_variables[*idx])
+            .collect();
+
+        if self.diversity > 1 {
+            self.block_similar_models(&vars);
+        } else {
+            // Solutions always have exactly subgraph_size vertices,
+            // so only the positive literals need to be negated
+            let lits: Vec<Literal> = vars
+                .iter()
+                .map(|v| v.neg_lit())
+                .collect();
+
+            self.backend.add_clause(&lits, &self.constraints.factory);
+        }
+    }
+
+    /**
+     * Restricts this solver to slice `index` of `count` disjoint slices of the search space,
+     * so independent solvers (eg one per thread or web worker) can split up an enumeration
+     *
+     * Solutions are assigned by their lowest vertex v, which must satisfy v % count == index.
+     * For every vertex u outside the slice, we add
+     *   u => (w1 | w2 | ...)
+     * over the vertices w < u in the slice, so u can only be picked alongside a lower vertex that is.
+     *
+     * Diversity is only enforced between solutions of the same slice.
+     * Like blocked solutions, these clauses aren't part of the exported constraints.
+     */
+    pub fn partition(&mut self, index: usize, count: usize) {
+        debug_assert!(index < count);
+
+        let mut lower_in_slice = Vec::<Literal>::new();
+
+        for (v, var) in self.solution_variables.iter().enumerate() {
+            if v % count == index {
+                lower_in_slice.push(var.pos_lit());
+                continue;
+            }
+
+            let mut clause = vec![var.neg_lit()];
+            clause.extend(lower_in_slice.iter().copied());
+
+            self.backend.add_clause(&clause, &self.constraints.factory);
+
+            // Skip sweeping roots outside the slice
+            self.root_weights[v] = 0.0;
+        }
+    }
+
+    /**
+     * Generalization of the usual blocking clause for a minimum distance of d
+     *
+     * Since every solution contains exactly subgraph_size vertices,
+     * differing from the latest model by at least d vertices is the same as
+     * sharing at most (subgraph_size - d) vertices with it.
+     *
+     * For example, if the latest model was { v1, v2, v3 } and d = 2 then we add
+     *   at-most-1(v1, v2, v3)
+     */
+    fn block_similar_models(&mut self, latest: &[Variable]) {
+        let max_shared = latest.len().saturating_sub(self.diversity);
+
+        let f = &self.constraints.factory;
+        let constraint = f.cc(
+            CType::LE,
+            max_shared as u64,
+            latest.to_vec()
+        );
+
+        self.backend.add_formula(constraint, f);
+    }
+}
+
+/**
+ * n choose k, as a float since it overflows quickly
+ */
+pub(super) fn num_combinations(n: usize, k: usize) -> f64 {
+    if k > n {
+        return 0.0;
+    }
+
+    (0..k).fold(1.0, |acc, i| (acc * ((n - i) as f64)) / ((i + 1) as f64))
+}
+
+// next() only exists here, like SubgraphEnumerator's
+impl SubgraphSearch for SubgraphSolver {
+    fn next(&mut self) -> Option<Solution> {
+        loop {
+            if self.root >= self.solution_variables.len() {
+                return None;
+            }
+
+            // No subgraph has this root (or it's outside this solver's partition)
+            if self.root_weights[self.root] == 0.0 {
+                self.root += 1;
+                continue;
+            }
+
+            let root = self.solution_variables[self.root];
+            if self.backend.solve(&[root.pos_lit()]) {
+                break;
+            }
+
+            // Every solution containing the root has been found,
+            // earlier sweeps covered the ones with a lower vertex
+            self.backend.add_clause(
+                &[root.neg_lit()],
+                &self.constraints.factory
+            );
+            self.root += 1;
+        }
+
+        let model = self.backend.model(&self.solution_variables)?;
+
+        if self.diversity > 1 {
+            let latest: Vec<Variable> = model
+                .iter()
+                .filter(|lit| lit.phase())
+                .map(|lit| lit.variable())
+                .collect();
+
+            self.block_similar_models(&latest);
+        } else {
+            self.backend.block_model(
+                &self.solution_variables,
+                &self.constraints.factory
+            );
+        }
+
+        self.solutions_found += 1;
+
+        Some(
+            self.constraints.variables.decode_vertices(
+                model.iter().map(|lit| (lit.variable(), lit.phase()))
+            )
+        )
+    }
+
+    fn block_solution(&mut self, vertices: &[usize]) {
+        SubgraphSolver::block_solution(self, vertices)
+    }
+
+    fn partition(&mut self, index: usize, count: usize) {
+        SubgraphSolver::partition(self, index, count)
+    }
+
+    fn progress(&self) -> SolverProgress {
+        SubgraphSolver::progress(self)
+    }
+
+    fn backbone(&self) -> Option<Backbone> {
+        vertex_backbone(&self.constraints, self.backend.fresh())
+    }
+
+    fn constraints(&self) -> Option<&SubgraphConstraints> {
+        Some(&self.constraints)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::sat::{
+        build_ab_graph,
+        build_degree_constraints,
+        build_kite_graph,
+        build_kite_graph_with,
+        build_square_graph,
+        ConnectivityEncoding,
+    };
+    use crate::team_mask::TeamMask;
+
+    use super::{ SubgraphSearch, SubgraphSolver, Solution };
+
+    pub fn vec_vec_to_hash_hash(
+        cnf: Vec<Solution>
+    ) -> HashSet<TeamMask> {
+        let result = HashSet::from_iter(
+            cnf
+                .clone()
+                .into_iter()
+                .map(|clause| {
+                    let hashed = TeamMask::from_iter(&clause);
+
+                    // No dupes should be removed, each variable should appear at most once
+                    assert_eq!(
+                        hashed.len(),
+                        clause.len(),
+                        "Duplicate variables removed from cnf clause {:?}",
+                        clause
+                    );
+
+                    hashed
+                })
+        );
+
+        // No dupes should be removed, each variable should appear at most once
+        assert_eq!(
+            result.len(),
+            cnf.len(),
+            "Duplicate clauses removed from {:?}",
+            cnf
+        );
+
+        result
+    }
+
+    fn collect(solver: &mut SubgraphSolver) -> Vec<Solution> {
+        let mut results = Vec::<Solution>::new();
+
+        while let Some(ans) = solver.next() {
+            results.push(ans);
+        }
+
+        results
+    }
+
+    /**
+     * Checks that each actual solution matches one of the expected solutions
+     *   eg to check if the subgraph defined by nodes { 3, 5 } is in the solution,
+     *      pass in [3, 5] or [5, 3]
+     */
+    fn assert_solutions(
+        actual: Vec<Solution>,
+        expected: Vec<Solution>
+    ) {
+        let actual = vec_vec_to_hash_hash(actual);
+        let expected = vec_vec_to_hash_hash(expected);
+
+        assert_eq!(
+            actual.len(),
+            expected.len(),
+            "Expected {} solutions but got {}: {:?}",
+            expected.len(),
+            actual.len(),
+            actual
+        );
+
+        for sol in expected.iter() {
+            assert!(
+                actual.contains(sol),
+                "Expected {:?} to be in {:?}",
+                sol,
+                actual
+            );
+        }
+    }
+
+    fn to_sols(ids: Vec<Vec<usize>>) -> Vec<Solution> {
+        ids
+    }
+
+    #[test]
+    fn test_ab1() {
+        let constraints = build_ab_graph(1);
+
+        let mut solver = SubgraphSolver::new(constraints);
+
+        let sols = collect(&mut solver);
+
+        assert_solutions(sols, to_sols(vec![vec![0], vec![1]]))
+    }
+
+    #[test]
+    fn test_ab2() {
+        let constraints = build_ab_graph(2);
+
+        let mut solver = SubgraphSolver::new(constraints);
+
+        let sols = collect(&mut solver);
+
+        assert_solutions(sols, to_sols(vec![vec![0, 1]]))
+    }
+
+    #[test]
+    fn test_square1() {
+        let constraints = build_square_graph(1);
+
+        let mut solver = SubgraphSolver::new(constraints);
+
+        let sols = collect(&mut solver);
+
+        assert_solutions(
+            sols,
+            to_sols(vec![vec![0], vec![1], vec![2], vec![3]])
+        )
+    }
+
+    #[test]
+    fn test_square2() {
+        let constraints = build_square_graph(2);
+
+        let mut solver = SubgraphSolver::new(constraints);
+
+        let sols = collect(&mut solver);
+
+        assert_solutions(
+            sols,
+            to_sols(
+                vec![vec![0, 1], vec![0, 2], vec![1, 3], vec![2, 3]]
+            )
+        )
+    }
+
+    #[test]
+    fn test_square3() {
+        let constraints = build_square_graph(3);
+
+        let mut solver = SubgraphSolver::new(constraints);
+
+        let sols = collect(&mut solver);
+
+        assert_solutions(
+            sols,
+            // Solutions clockwise
+            to_sols(
+                vec![
+                    vec![0, 1, 3],
+                    vec![1, 3, 2],
+                    vec![3, 2, 0],
+                    vec![2, 0, 1]
+                ]
+            )
+        )
+    }
+
+    #[test]
+    fn test_square4() {
+        let constraints = build_square_graph(4);
+
+        let mut solver = SubgraphSolver::new(constraints);
+
+        let sols = collect(&mut solver);
+
+        assert_solutions(
+            sols,
+            // Solutions clockwise
+            to_sols(vec![vec![0, 1, 2, 3]])
+        )
+    }
+
+    #[test]
+    fn test_kite1() {
+        let constraints = build_kite_graph(1);
+
+        let mut solver = SubgraphSolver::new(constraints);
+
+        let sols = collect(&mut solver);
+
+        assert_solutions(
+            sols,
+            to_sols(vec![vec![0], vec![1], vec![2], vec![3], vec![4]])
+        )
+    }
+
+    #[test]
+    fn test_kite2() {
+        let constraints = build_kite_graph(2);
+
+        let mut solver = SubgraphSolver::new(constraints);
+
+        let sols = collect(&mut solver);
+
+        assert_solutions(
+            sols,
+            to_sols(
+                vec![
+                    vec![0, 1],
+                    vec![0, 2],
+                    vec![1, 3],
+                    vec![2, 3],
+                    vec![3, 4]
+                ]
+            )
+        )
+    }
+
+    #[test]
+    fn test_kite3() {
+        let constraints = build_kite_graph(3);
+
+        let mut solver = SubgraphSolver::new(constraints);
+
+        let sols = collect(&mut solver);
+
+        assert_solutions(
+            sols,
+            // Solutions clockwise
+            to_sols(
+                vec![
+                    vec![0, 2, 3],
+                    vec![2, 3, 1],
+                    vec![3, 1, 0],
+                    vec![1, 0, 2],
+                    vec![1, 3, 4],
+                    vec![2, 3, 4]
+                ]
+            )
+        )
+    }
+
+    #[test]
+    fn test_kite4() {
+        let constraints = build_kite_graph(4);
+
+        let mut solver = SubgraphSolver::new(constraints);
+
+        let sols = collect(&mut solver);
+
+        assert_solutions(
+            sols,
+            to_sols(
+                vec![
+                    vec![1, 2, 3, 4], // all but 0
+                    vec![0, 2, 3, 4], // all but 1
+                    vec![0, 1, 3, 4], // all but 2
+                    vec![0, 1, 2, 3] // all but 4
+                    // but removing 3 also removes 4, so a 4-graph can't be constructed
+                ]
+            )
+        )
+    }
+
+    #[test]
+    fn test_kite5() {
+        let constraints = build_kite_graph(5);
+
+        let mut solver = SubgraphSolver::new(constraints);
+
+        let sols = collect(&mut solver);
+
+        assert_solutions(
+            sols,
+            to_sols(vec![vec![0, 1, 2, 3, 4]])
+        )
+    }
+
+    /**
+     * Checks that every pair of solutions differs by at least min_difference vertices
+     */
+    fn assert_diverse(
+        actual: Vec<Solution>,
+        min_difference: usize
+    ) {
+        let teams: Vec<TeamMask> = actual
+            .iter()
+            .map(TeamMask::from_iter)
+            .collect();
+
+        for (i, a) in teams.iter().enumerate() {
+            for b in teams.iter().skip(i + 1) {
+                let num_different = a.num_different(b);
+
+                assert!(
+                    num_different >= min_difference,
+                    "Expected {:?} and {:?} to differ by at least {} vertices",
+                    a,
+                    b,
+                    min_difference
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_square2_diverse() {
+        let constraints = build_square_graph(2);
+
+        let mut solver = SubgraphSolver::new(constraints);
+        solver.diversity = 2;
+
+        let sols = collect(&mut solver);
+
+        // Only two disjoint edges fit in a square
+        assert_eq!(sols.len(), 2);
+        assert_diverse(sols, 2);
+    }
+
+    #[test]
+    fn test_kite3_diverse() {
+        let constraints = build_kite_graph(3);
+
+        let mut solver = SubgraphSolver::new(constraints);
+        solver.diversity = 2;
+
+        let sols = collect(&mut solver);
+
+        assert!(!sols.is_empty());
+        assert_diverse(sols, 2);
+    }
+
+    #[test]
+    fn test_kite3_resume() {
+        let mut first = SubgraphSolver::new(build_kite_graph(3));
+        let before: Vec<Solution> = (0..2)
+            .map(|_| first.next().unwrap())
+            .collect();
+
+        // Replay the first two solutions onto a fresh solver
+        let constraints = build_kite_graph(3);
+
+        let mut second = SubgraphSolver::new(constraints);
+        for sol in before.iter() {
+            second.block_solution(sol);
+        }
+
+        let mut sols = before;
+        sols.extend(collect(&mut second));
+
+        assert_solutions(
+            sols,
+            to_sols(
+                vec![
+                    vec![0, 2, 3],
+                    vec![2, 3, 1],
+                    vec![3, 1, 0],
+                    vec![1, 0, 2],
+                    vec![1, 3, 4],
+                    vec![2, 3, 4]
+                ]
+            )
+        )
+    }
+
+    #[test]
+    fn test_partition() {
+        let mut expected = collect(
+            &mut SubgraphSolver::new(build_kite_graph(3))
+        );
+        expected.sort();
+
+        let mut actual = vec![];
+        for index in 0..3 {
+            let mut solver = SubgraphSolver::new(build_kite_graph(3));
+            solver.partition(index, 3);
+
+            for sol in collect(&mut solver) {
+                assert_eq!(sol[0] % 3, index);
+                actual.push(sol);
+            }
+        }
+        actual.sort();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_progress() {
+        let mut solver = SubgraphSolver::new(build_kite_graph(3));
+
+        // C(5, 3) vertex sets
+        let progress = solver.progress();
+        assert_eq!(progress.search_space, 10.0);
+        assert_eq!(progress.fraction_searched(), 0.0);
+
+        // All 3 solutions with root 0 come before any others,
+        // then the sweep of root 0 covers C(4, 2) vertex sets
+        for _ in 0..3 {
+            assert_eq!(solver.next().unwrap()[0], 0);
+        }
+        solver.next();
+        assert_eq!(solver.progress().searched, 6.0);
+
+        collect(&mut solver);
+        let progress = solver.progress();
+        assert_eq!(progress.solutions_found, 6);
+        assert_eq!(progress.fraction_searched(), 1.0);
+    }
+
+    #[test]
+    fn test_sparse_encoding() {
+        for size in 1..=5 {
+            let mut expected = collect(
+                &mut SubgraphSolver::new(build_kite_graph(size))
+            );
+            expected.sort();
+
+            let mut actual = collect(
+                &mut SubgraphSolver::new(
+                    build_kite_graph_with(size, ConnectivityEncoding::Sparse)
+                )
+            );
+            actual.sort();
+
+            assert_eq!(actual, expected, "size {}", size);
+        }
+    }
+
+    #[test]
+    fn test_min_degree() {
+        let kite_edges = HashSet::from_iter([
+            (0, 1),
+            (0, 2),
+            (1, 3),
+            (2, 3),
+            (3, 4),
+        ]);
+        let solve = |size: i32, min_degree: usize| {
+            let mut constraints = build_kite_graph(size);
+            build_degree_constraints(
+                min_degree,
+                &kite_edges,
+                &mut constraints
+            );
+
+            let mut solutions = collect(&mut SubgraphSolver::new(constraints));
+            solutions.sort();
+            solutions
+        };
+
+        // Connectivity already implies a degree of 1
+        assert_eq!(solve(3, 1), solve(3, 0));
+        assert_eq!(solve(1, 1), Vec::<Solution>::new());
+
+        // e only has one neighbour, and there are no triangles
+        assert_eq!(solve(3, 2), Vec::<Solution>::new());
+        assert_eq!(solve(4, 2), to_sols(vec![vec![0, 1, 2, 3]]));
+        assert_eq!(solve(5, 2), Vec::<Solution>::new());
+        assert_eq!(solve(4, 3), Vec::<Solution>::new());
+    }
+}
@@ -0,0 +1,276 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use logicng::formulas::{ FormulaFactory, Literal, Variable };
+use serde::{ Deserialize, Serialize };
+
+use super::{ cnf_clauses, Constraint };
+
+/**
+ * Which part of the encoding a constraint belongs to
+ *
+ * Families can be left out of a search (see SearchOptions.disabled_constraints)
+ * to find out which one makes it slow or unsatisfiable
+ */
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ConstraintFamily {
+    // Links vertices to the timestep they're picked at,
+    // with exactly one vertex per timestep
+    Decisions,
+    // Each vertex picked after the first shares an edge with an earlier one
+    Connectivity,
+    // A vertex can't be picked at two timesteps
+    NoRepeat,
+    // Fixes the edge variables to the graph's edges
+    Edges,
+    // Each slot is filled by one of its options
+    SlotCoverage,
+    // A single vertex can't fill two slots
+    SlotDedupe,
+    // Each picked vertex shares an edge with enough other picked vertices
+    MinDegree,
+    // Side constraints added to a ConnectedSubgraphProblem
+    Custom,
+    // Clauses read from a DIMACS file
+    Imported,
+}
+
+impl ConstraintFamily {
+    pub const ALL: [ConstraintFamily; 9] = [
+        Self::Decisions,
+        Self::Connectivity,
+        Self::NoRepeat,
+        Self::Edges,
+        Self::SlotCoverage,
+        Self::SlotDedupe,
+        Self::MinDegree,
+        Self::Custom,
+        Self::Imported,
+    ];
+
+    /**
+     * Same as the serialized name
+     */
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Decisions => "decisions",
+            Self::Connectivity => "connectivity",
+            Self::NoRepeat => "no_repeat",
+            Self::Edges => "edges",
+            Self::SlotCoverage => "slot_coverage",
+            Self::SlotDedupe => "slot_dedupe",
+            Self::MinDegree => "min_degree",
+            Self::Custom => "custom",
+            Self::Imported => "imported",
+        }
+    }
+
+    pub fn from_label(label: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|family| family.label() == label)
+    }
+}
+
+impl fmt::Display for ConstraintFamily {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.label())
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct ConstraintStats {
+    // Including the auxiliary variables introduced by the CNF conversion
+    pub variables: usize,
+    pub clauses: usize,
+    pub literals: usize,
+    pub families: Vec<FamilyStats>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct FamilyStats {
+    pub family: ConstraintFamily,
+    pub constraints: usize,
+    pub clauses: usize,
+    pub literals: usize,
+}
+
+impl ConstraintStats {
+    /**
+     * Counts the clauses each constraint converts to,
+     * which only takes encoding the cardinality constraints
+     * since everything else already is a clause
+     */
+    pub fn of(
+        constraints: &[(ConstraintFamily, Constraint)],
+        f: &FormulaFactory
+    ) -> Self {
+        let mut stats = Self::default();
+        // Auxiliary variables of the encodings are named, so this covers them
+        let mut variables = HashSet::<Variable>::new();
+
+        for (family, constraint) in constraints.iter() {
+            let mut count = ClauseCount::default();
+            let mut add_clause = |clause: &[Literal]| {
+                variables.extend(clause.iter().map(|lit| lit.variable()));
+                count.clauses += 1;
+                count.literals += clause.len();
+            };
+
+            match constraint {
+                Constraint::Clause(clause) => add_clause(clause),
+                Constraint::Formula(formula) => {
+                    for clause in cnf_clauses(f.cnf_of(*formula), f) {
+                        add_clause(&clause);
+                    }
+                }
+            }
+
+            stats.clauses += count.clauses;
+            stats.literals += count.literals;
+
+            let idx = match stats.families
+                .iter()
+                .position(|s| s.family == *family)
+            {
+                Some(idx) => idx,
+                None => {
+                    stats.families.push(FamilyStats {
+                        family: *family,
+                        constraints: 0,
+                        clauses: 0,
+                        literals: 0,
+                    });
+                    stats.families.len() - 1
+                }
+            };
+
+            let family_stats = &mut stats.families[idx];
+            family_stats.constraints += 1;
+            family_stats.clauses += count.clauses;
+            family_stats.literals += count.literals;
+        }
+
+        stats.variables = variables.len();
+        stats
+    }
+
+    /**
+     * Totals over independent sets of constraints (eg one per component),
+     * which don't share any variables
+     */
+    pub fn merge(&mut self, other: &ConstraintStats) {
+        self.variables += other.variables;
+        self.clauses += other.clauses;
+        self.literals += other.literals;
+
+        for family_stats in other.families.iter() {
+            match self.families
+                .iter_mut()
+                .find(|s| s.family == family_stats.family)
+            {
+                Some(existing) => {
+                    existing.constraints += family_stats.constraints;
+                    existing.clauses += family_stats.clauses;
+                    existing.literals += family_stats.literals;
+                }
+                None => {
+                    self.families.push(family_stats.clone());
+                }
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+struct ClauseCount {
+    clauses: usize,
+    literals: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sat::build_kite_graph;
+
+    use super::{ ConstraintFamily, FamilyStats };
+
+    #[test]
+    fn test_kite() {
+        // 5 vertices, 3 timesteps
+        let constraints = build_kite_graph(3);
+        let stats = constraints.stats();
+
+        let family = |family: ConstraintFamily| {
+            stats.families
+                .iter()
+                .find(|s| s.family == family)
+                .unwrap()
+                .clone()
+        };
+
+        // 25 unit clauses fixing the edges,
+        // plus 20 implications making them undirected
+        assert_eq!(
+            family(ConstraintFamily::Edges),
+            FamilyStats {
+                family: ConstraintFamily::Edges,
+                constraints: 45,
+                clauses: 45,
+                literals: 65,
+            }
+        );
+
+        // One binary clause per vertex and pair of timesteps
+        assert_eq!(
+            family(ConstraintFamily::NoRepeat),
+            FamilyStats {
+                family: ConstraintFamily::NoRepeat,
+                constraints: 15,
+                clauses: 15,
+                literals: 30,
+            }
+        );
+
+        // d_t_i => l_0_0_i | ... has one clause with 5t + 1 literals,
+        // and each link variable is defined by 2 binary clauses
+        // (the 25 for t0 = 0 are shared between t = 1 and t = 2)
+        assert_eq!(
+            family(ConstraintFamily::Connectivity),
+            FamilyStats {
+                family: ConstraintFamily::Connectivity,
+                constraints: 10 + 50 * 2,
+                clauses: 10 + 50 * 2,
+                literals: 5 * 6 + 5 * 11 + 50 * 2 * 2,
+            }
+        );
+
+        assert_eq!(
+            stats.families
+                .iter()
+                .map(|s| s.clauses)
+                .sum::<usize>(),
+            stats.clauses
+        );
+        assert_eq!(
+            stats.families
+                .iter()
+                .map(|s| s.constraints)
+                .sum::<usize>(),
+            constraints.num_constraints()
+        );
+
+        // 5 vertices, 25 edges, 15 decisions and the 50 link variables above,
+        // plus any introduced by the exactly-one constraints
+        assert!(stats.variables >= 95);
+    }
+}
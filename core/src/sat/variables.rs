@@ -0,0 +1,351 @@
+use std::collections::HashMap;
+
+use logicng::formulas::{ FormulaFactory, Variable };
+
+/**
+ * What a SAT variable in the subgraph encoding stands for
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TeamVariable {
+    // Vertex i is in the subgraph
+    Vertex(usize),
+    // Vertices i and j share an edge
+    Edge(usize, usize),
+    // Vertex j is picked at timestep t
+    Decision(usize, usize),
+    // Slot s is filled by vertex j
+    Slot(usize, usize),
+    // Vertex j is picked at timestep t and shares an edge with vertex i
+    // (auxiliary variable of the dense connectivity encoding)
+    Link(usize, usize, usize),
+    // At least r of the first j neighbours of vertex i are picked
+    // (auxiliary variable of the min degree encoding)
+    Counter(usize, usize, usize),
+}
+
+/**
+ * Allocates the variables of the subgraph encoding ahead of the constraints
+ * so constraint builders can look them up by index
+ * instead of formatting and hashing names for each clause.
+ * Vertices and decisions always exist, the others are only allocated
+ * by the encodings that use them.
+ *
+ * Variables are still named (v{i}, e_{i}_{j}, d_{t}_{j}, s_{s}_{j}, l_{t}_{j}_{i}, c_{i}_{j}_{r})
+ * so the formulas stay readable when printed or exported to DIMACS.
+ */
+pub struct VariableRegistry {
+    num_vertices: usize,
+    subgraph_size: usize,
+    vertices: Vec<Variable>,
+    // Only allocated by allocate_edges(),
+    // num_vertices x num_vertices, row major
+    edges: Vec<Variable>,
+    // subgraph_size x num_vertices, row major
+    decisions: Vec<Variable>,
+    // Only allocated for the options of each slot, see allocate_slots()
+    slots: Vec<HashMap<usize, Variable>>,
+    // Only allocated by allocate_links(),
+    // (subgraph_size - 1) x num_vertices x num_vertices, row major
+    links: Vec<Variable>,
+    // Only allocated by allocate_counters(), keyed by (i, j, r)
+    counters: HashMap<(usize, usize, usize), Variable>,
+    decoded: HashMap<Variable, TeamVariable>,
+}
+
+impl VariableRegistry {
+    pub fn new(
+        f: &FormulaFactory,
+        num_vertices: usize,
+        subgraph_size: usize
+    ) -> Self {
+        let mut registry = Self {
+            num_vertices,
+            subgraph_size,
+            vertices: Vec::with_capacity(num_vertices),
+            edges: vec![],
+            decisions: Vec::with_capacity(subgraph_size * num_vertices),
+            slots: vec![],
+            links: vec![],
+            counters: HashMap::new(),
+            decoded: HashMap::new(),
+        };
+
+        for i in 0..num_vertices {
+            let v = registry.allocate(f, TeamVariable::Vertex(i));
+            registry.vertices.push(v);
+        }
+
+        for t in 0..subgraph_size {
+            for j in 0..num_vertices {
+                let v = registry.allocate(
+                    f,
+                    TeamVariable::Decision(t, j)
+                );
+                registry.decisions.push(v);
+            }
+        }
+
+        registry
+    }
+
+    fn allocate(
+        &mut self,
+        f: &FormulaFactory,
+        kind: TeamVariable
+    ) -> Variable {
+        let name = match kind {
+            TeamVariable::Vertex(i) => format!("v{}", i),
+            TeamVariable::Edge(i, j) => format!("e_{}_{}", i, j),
+            TeamVariable::Decision(t, j) => format!("d_{}_{}", t, j),
+            TeamVariable::Slot(s, j) => format!("s_{}_{}", s, j),
+            TeamVariable::Link(t, j, i) => format!("l_{}_{}_{}", t, j, i),
+            TeamVariable::Counter(i, j, r) => {
+                format!("c_{}_{}_{}", i, j, r)
+            }
+        };
+
+        let var = f.var(&name);
+        self.decoded.insert(var, kind);
+
+        var
+    }
+
+    pub fn vertex(&self, i: usize) -> Variable {
+        self.vertices[i]
+    }
+
+    pub fn edge(&self, i: usize, j: usize) -> Variable {
+        self.edges[i * self.num_vertices + j]
+    }
+
+    pub fn decision(&self, t: usize, j: usize) -> Variable {
+        self.decisions[t * self.num_vertices + j]
+    }
+
+    /**
+     * Adds a variable for each option of each slot
+     * (replacing any from a previous call)
+     */
+    pub fn allocate_slots(
+        &mut self,
+        f: &FormulaFactory,
+        slot_options: &[Vec<usize>]
+    ) {
+        self.slots = vec![];
+
+        for (s, options) in slot_options.iter().enumerate() {
+            let mut vars = HashMap::new();
+            for j in options.iter() {
+                let v = self.allocate(f, TeamVariable::Slot(s, *j));
+                vars.insert(*j, v);
+            }
+
+            self.slots.push(vars);
+        }
+    }
+
+    /**
+     * Adds an edge variable for every (ordered) pair of vertices,
+     * only the dense connectivity encoding refers to them
+     */
+    pub fn allocate_edges(&mut self, f: &FormulaFactory) {
+        let n = self.num_vertices;
+
+        self.edges = Vec::with_capacity(n * n);
+        for i in 0..n {
+            for j in 0..n {
+                let v = self.allocate(f, TeamVariable::Edge(i, j));
+                self.edges.push(v);
+            }
+        }
+    }
+
+    /**
+     * Adds a link variable for every timestep but the last
+     * and every (ordered) pair of vertices
+     */
+    pub fn allocate_links(&mut self, f: &FormulaFactory) {
+        let n = self.num_vertices;
+        let num_timesteps = self.subgraph_size.saturating_sub(1);
+
+        self.links = Vec::with_capacity(num_timesteps * n * n);
+        for t in 0..num_timesteps {
+            for j in 0..n {
+                for i in 0..n {
+                    let v = self.allocate(f, TeamVariable::Link(t, j, i));
+                    self.links.push(v);
+                }
+            }
+        }
+    }
+
+    pub fn link(&self, t: usize, j: usize, i: usize) -> Variable {
+        let n = self.num_vertices;
+        self.links[(t * n + j) * n + i]
+    }
+
+    /**
+     * Adds the counters of a sequential counter over the neighbours of each vertex,
+     * up to min_degree (replacing any from a previous call)
+     *
+     * Counters only exist for 1 <= r <= min(j, min_degree),
+     * and aren't needed at all if min_degree is 1
+     * or a vertex has fewer neighbours than that
+     */
+    pub fn allocate_counters(
+        &mut self,
+        f: &FormulaFactory,
+        degrees: &[usize],
+        min_degree: usize
+    ) {
+        self.counters = HashMap::new();
+        if min_degree <= 1 {
+            return;
+        }
+
+        for (i, degree) in degrees.iter().enumerate() {
+            if *degree < min_degree {
+                continue;
+            }
+
+            for j in 1..=*degree {
+                for r in 1..=j.min(min_degree) {
+                    let v = self.allocate(f, TeamVariable::Counter(i, j, r));
+                    self.counters.insert((i, j, r), v);
+                }
+            }
+        }
+    }
+
+    pub fn counter(&self, i: usize, j: usize, r: usize) -> Option<Variable> {
+        self.counters.get(&(i, j, r)).copied()
+    }
+
+    /**
+     * None if vertex j isn't an option for slot s
+     */
+    pub fn slot(&self, s: usize, j: usize) -> Option<Variable> {
+        self.slots.get(s)?.get(&j).copied()
+    }
+
+    pub fn vertices(&self) -> &[Variable] {
+        &self.vertices
+    }
+
+    pub fn decisions_at(&self, t: usize) -> &[Variable] {
+        let start = t * self.num_vertices;
+        &self.decisions[start..start + self.num_vertices]
+    }
+
+    /**
+     * Variables introduced elsewhere (eg by CNF conversion) decode to None
+     */
+    pub fn decode(&self, var: Variable) -> Option<TeamVariable> {
+        self.decoded.get(&var).copied()
+    }
+
+    /**
+     * Indices of the vertices assigned true in a model, ascending
+     */
+    pub fn decode_vertices(
+        &self,
+        model: impl IntoIterator<Item = (Variable, bool)>
+    ) -> Vec<usize> {
+        let mut result: Vec<usize> = model
+            .into_iter()
+            .filter(|(_, phase)| *phase)
+            .filter_map(|(var, _)| match self.decode(var) {
+                Some(TeamVariable::Vertex(i)) => Some(i),
+                _ => None,
+            })
+            .collect();
+
+        result.sort();
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use logicng::formulas::FormulaFactory;
+
+    use super::{ TeamVariable, VariableRegistry };
+
+    #[test]
+    fn test_lookup() {
+        let f = FormulaFactory::new();
+        let mut registry = VariableRegistry::new(&f, 3, 2);
+
+        // Only the dense encoding has edge variables
+        assert_eq!(registry.decode(f.var("e_1_2")), None);
+        registry.allocate_edges(&f);
+
+        assert_eq!(registry.vertex(2).name(&f), "v2");
+        assert_eq!(registry.edge(1, 2).name(&f), "e_1_2");
+        assert_eq!(registry.decision(1, 0).name(&f), "d_1_0");
+        assert_eq!(registry.decisions_at(1)[2], registry.decision(1, 2));
+
+        assert_eq!(
+            registry.decode(registry.edge(2, 1)),
+            Some(TeamVariable::Edge(2, 1))
+        );
+        assert_eq!(registry.decode(f.var("x")), None);
+    }
+
+    #[test]
+    fn test_links() {
+        let f = FormulaFactory::new();
+        let mut registry = VariableRegistry::new(&f, 3, 3);
+        registry.allocate_links(&f);
+
+        let var = registry.link(1, 2, 0);
+        assert_eq!(var.name(&f), "l_1_2_0");
+        assert_eq!(registry.decode(var), Some(TeamVariable::Link(1, 2, 0)));
+        assert_ne!(registry.link(0, 1, 2), registry.link(0, 2, 1));
+    }
+
+    #[test]
+    fn test_counters() {
+        let f = FormulaFactory::new();
+        let mut registry = VariableRegistry::new(&f, 3, 3);
+        registry.allocate_counters(&f, &[2, 1, 3], 2);
+
+        let var = registry.counter(2, 3, 2).unwrap();
+        assert_eq!(var.name(&f), "c_2_3_2");
+        assert_eq!(registry.decode(var), Some(TeamVariable::Counter(2, 3, 2)));
+
+        // Fewer neighbours than the minimum, or more than needed
+        assert_eq!(registry.counter(1, 1, 1), None);
+        assert_eq!(registry.counter(2, 1, 2), None);
+        assert_eq!(registry.counter(2, 3, 3), None);
+    }
+
+    #[test]
+    fn test_slots() {
+        let f = FormulaFactory::new();
+        let mut registry = VariableRegistry::new(&f, 3, 2);
+        registry.allocate_slots(&f, &[vec![0, 2], vec![1]]);
+
+        let var = registry.slot(0, 2).unwrap();
+        assert_eq!(var.name(&f), "s_0_2");
+        assert_eq!(registry.decode(var), Some(TeamVariable::Slot(0, 2)));
+
+        assert_eq!(registry.slot(0, 1), None);
+        assert_eq!(registry.slot(2, 0), None);
+    }
+
+    #[test]
+    fn test_decode_vertices() {
+        let f = FormulaFactory::new();
+        let registry = VariableRegistry::new(&f, 3, 2);
+
+        let model = [
+            (registry.vertex(2), true),
+            (registry.vertex(1), false),
+            (registry.decision(0, 1), true),
+            (registry.vertex(0), true),
+        ];
+
+        assert_eq!(registry.decode_vertices(model), vec![0, 2]);
+    }
+}
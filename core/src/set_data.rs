@@ -0,0 +1,336 @@
+use std::collections::{ HashMap, HashSet };
+use std::fmt;
+
+use serde::{ Deserialize, Serialize };
+
+/**
+ * Champions and traits for the current set,
+ * as exported by scripts/download_champions.py and scripts/download_traits.py
+ *
+ * Outside the core champions are referenced by their id (eg TFT11_Ahri),
+ * which is stable across patches. Internally they're referenced by
+ * their index in the champions list, see index_of()
+ *
+ * Only built through new() or from_json(),
+ * so the champions can't get out of sync with their indices
+ */
+#[derive(Debug, Default, Clone)]
+pub struct SetData {
+    champions: Vec<ChampionData>,
+    pub traits: Vec<TraitData>,
+
+    // Champion id -> index
+    indices: HashMap<String, usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChampionData {
+    // eg TFT11_Ahri
+    #[serde(rename = "character_id")]
+    pub id: String,
+    pub display_name: String,
+    #[serde(rename = "tier")]
+    pub cost: u8,
+    pub traits: Vec<ChampionTrait>,
+    pub damage_type: DamageType,
+    pub stats: ChampionStats,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChampionTrait {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DamageType {
+    pub is_ad: bool,
+    pub is_ap: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChampionStats {
+    pub damage: Option<f64>,
+    pub mana: Option<f64>,
+    pub range: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraitData {
+    // eg TFT11_Fated
+    #[serde(rename = "trait_id")]
+    pub id: String,
+    pub display_name: String,
+    pub levels: Vec<TraitLevel>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraitLevel {
+    pub min_units: u8,
+    pub style_name: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum SetDataError {
+    InvalidJson(String),
+    DuplicateChampion(String),
+    DuplicateTrait(String),
+    // Trait shared by multiple champions that is missing from traits.json
+    UnknownTrait {
+        champion: String,
+        trait_id: String,
+    },
+    MissingBreakpoints(String),
+}
+
+impl fmt::Display for SetDataError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidJson(e) => write!(f, "Invalid set data: {}", e),
+            Self::DuplicateChampion(id) => {
+                write!(f, "Champion {} is listed more than once", id)
+            }
+            Self::DuplicateTrait(id) => {
+                write!(f, "Trait {} is listed more than once", id)
+            }
+            Self::UnknownTrait { champion, trait_id } => {
+                write!(
+                    f,
+                    "Champion {} has unknown trait {}",
+                    champion,
+                    trait_id
+                )
+            }
+            Self::MissingBreakpoints(id) => {
+                write!(f, "Trait {} has no breakpoints", id)
+            }
+        }
+    }
+}
+
+impl SetData {
+    /**
+     * Parses the contents of merged_teamplanner_data.json and traits.json
+     */
+    pub fn from_json(
+        champions_json: &str,
+        traits_json: &str
+    ) -> Result<Self, SetDataError> {
+        let champions: Vec<ChampionData> = serde_json
+            ::from_str(champions_json)
+            .map_err(|e| SetDataError::InvalidJson(e.to_string()))?;
+        let traits: Vec<TraitData> = serde_json
+            ::from_str(traits_json)
+            .map_err(|e| SetDataError::InvalidJson(e.to_string()))?;
+
+        Self::new(champions, traits)
+    }
+
+    pub fn new(
+        champions: Vec<ChampionData>,
+        traits: Vec<TraitData>
+    ) -> Result<Self, SetDataError> {
+        let indices = HashMap::from_iter(
+            champions
+                .iter()
+                .enumerate()
+                .map(|(idx, c)| (c.id.clone(), idx))
+        );
+
+        let data = Self { champions, traits, indices };
+        data.validate()?;
+
+        Ok(data)
+    }
+
+    /**
+     * In the order they were loaded in, see index_of()
+     */
+    pub fn champions(&self) -> &[ChampionData] {
+        &self.champions
+    }
+
+    pub fn index_of(&self, id: &str) -> Option<usize> {
+        self.indices.get(id).copied()
+    }
+
+    pub fn validate(&self) -> Result<(), SetDataError> {
+        let mut champion_ids = HashSet::new();
+        for champion in self.champions.iter() {
+            if !champion_ids.insert(&champion.id) {
+                return Err(
+                    SetDataError::DuplicateChampion(champion.id.clone())
+                );
+            }
+        }
+
+        let mut trait_ids = HashSet::new();
+        for t in self.traits.iter() {
+            if !trait_ids.insert(&t.id) {
+                return Err(SetDataError::DuplicateTrait(t.id.clone()));
+            }
+
+            if t.levels.is_empty() {
+                return Err(
+                    SetDataError::MissingBreakpoints(t.id.clone())
+                );
+            }
+        }
+
+        // The download script skips unique traits,
+        // so only traits shared by multiple champions need to be known
+        for (trait_id, members) in self.champions_by_trait() {
+            if members.len() > 1 && !trait_ids.contains(&trait_id) {
+                let champion = &self.champions[members[0]];
+
+                return Err(SetDataError::UnknownTrait {
+                    champion: champion.id.clone(),
+                    trait_id,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Trait id -> indices of the champions with that trait
+     */
+    pub fn champions_by_trait(&self) -> HashMap<String, Vec<usize>> {
+        let mut result = HashMap::<String, Vec<usize>>::new();
+
+        for (idx, champion) in self.champions.iter().enumerate() {
+            for t in champion.traits.iter() {
+                result
+                    .entry(t.id.clone())
+                    .or_default()
+                    .push(idx);
+            }
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ SetData, SetDataError };
+
+    fn champion(id: &str, traits: &[&str]) -> String {
+        let traits: Vec<String> = traits
+            .iter()
+            .map(|t| format!(r#"{{ "id": "{}", "name": "{}" }}"#, t, t))
+            .collect();
+
+        format!(
+            r#"{{
+                "character_id": "{}",
+                "display_name": "{}",
+                "tier": 1,
+                "traits": [{}],
+                "damage_type": {{ "is_ad": true, "is_ap": false }},
+                "stats": {{ "damage": 50, "mana": null, "range": 4 }},
+                "spell": "<p></p>"
+            }}"#,
+            id,
+            id,
+            traits.join(",")
+        )
+    }
+
+    fn trait_(id: &str, breakpoints: &[u8]) -> String {
+        let levels: Vec<String> = breakpoints
+            .iter()
+            .map(|n|
+                format!(
+                    r#"{{ "min_units": {}, "style_name": "kBronze" }}"#,
+                    n
+                )
+            )
+            .collect();
+
+        format!(
+            r#"{{
+                "trait_id": "{}",
+                "display_name": "{}",
+                "tooltip_html": "",
+                "levels": [{}]
+            }}"#,
+            id,
+            id,
+            levels.join(",")
+        )
+    }
+
+    fn parse(
+        champions: &[String],
+        traits: &[String]
+    ) -> Result<SetData, SetDataError> {
+        SetData::from_json(
+            &format!("[{}]", champions.join(",")),
+            &format!("[{}]", traits.join(","))
+        )
+    }
+
+    #[test]
+    fn test_parse() {
+        let data = parse(
+            &[
+                champion("TFT_A", &["Fated", "Unique"]),
+                champion("TFT_B", &["Fated"]),
+            ],
+            &[trait_("Fated", &[3, 5, 7])]
+        ).unwrap();
+
+        assert_eq!(data.champions().len(), 2);
+        assert_eq!(data.champions()[0].id, "TFT_A");
+        assert_eq!(data.champions()[0].stats.mana, None);
+        assert_eq!(data.traits[0].levels[2].min_units, 7);
+        assert_eq!(data.champions_by_trait()["Fated"], vec![0, 1]);
+        assert_eq!(data.index_of("TFT_B"), Some(1));
+        assert_eq!(data.index_of("TFT_C"), None);
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(
+            matches!(
+                parse(&["{}".to_string()], &[]),
+                Err(SetDataError::InvalidJson(_))
+            )
+        );
+
+        assert_eq!(
+            parse(
+                &[champion("TFT_A", &[]), champion("TFT_A", &[])],
+                &[]
+            ).unwrap_err(),
+            SetDataError::DuplicateChampion("TFT_A".to_string())
+        );
+
+        assert_eq!(
+            parse(&[], &[trait_("Fated", &[3]), trait_("Fated", &[3])])
+                .unwrap_err(),
+            SetDataError::DuplicateTrait("Fated".to_string())
+        );
+
+        assert_eq!(
+            parse(&[], &[trait_("Fated", &[])]).unwrap_err(),
+            SetDataError::MissingBreakpoints("Fated".to_string())
+        );
+
+        assert_eq!(
+            parse(
+                &[
+                    champion("TFT_A", &["Fated"]),
+                    champion("TFT_B", &["Fated"]),
+                ],
+                &[]
+            ).unwrap_err(),
+            SetDataError::UnknownTrait {
+                champion: "TFT_A".to_string(),
+                trait_id: "Fated".to_string(),
+            }
+        );
+    }
+}
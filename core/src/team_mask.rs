@@ -0,0 +1,236 @@
+use std::fmt;
+
+/**
+ * Set of champion (or vertex) indices as a bitset,
+ * so teams can be hashed and compared without sorting
+ *
+ * Trailing zero words are trimmed, so equal sets are always
+ * equal (and hash the same) regardless of how they were built
+ */
+#[derive(Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TeamMask {
+    words: Vec<u64>,
+}
+
+impl TeamMask {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, idx: usize) {
+        let word = idx / 64;
+        if word >= self.words.len() {
+            self.words.resize(word + 1, 0);
+        }
+
+        self.words[word] |= 1 << (idx % 64);
+    }
+
+    pub fn contains(&self, idx: usize) -> bool {
+        self.words
+            .get(idx / 64)
+            .is_some_and(|word| (word >> (idx % 64)) & 1 == 1)
+    }
+
+    pub fn len(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    pub fn union(&self, other: &TeamMask) -> TeamMask {
+        let (longer, shorter) = if self.words.len() >= other.words.len() {
+            (self, other)
+        } else {
+            (other, self)
+        };
+
+        let mut words = longer.words.clone();
+        for (word, other) in words.iter_mut().zip(shorter.words.iter()) {
+            *word |= other;
+        }
+
+        Self { words }
+    }
+
+    pub fn intersection(&self, other: &TeamMask) -> TeamMask {
+        Self::trimmed(
+            self.words
+                .iter()
+                .zip(other.words.iter())
+                .map(|(a, b)| a & b)
+                .collect()
+        )
+    }
+
+    /**
+     * Indices in self but not in other
+     */
+    pub fn difference(&self, other: &TeamMask) -> TeamMask {
+        Self::trimmed(
+            self.words
+                .iter()
+                .enumerate()
+                .map(|(i, a)| a & !other.words.get(i).unwrap_or(&0))
+                .collect()
+        )
+    }
+
+    /**
+     * Size of the intersection, without allocating it
+     */
+    pub fn num_shared(&self, other: &TeamMask) -> usize {
+        self.words
+            .iter()
+            .zip(other.words.iter())
+            .map(|(a, b)| (a & b).count_ones() as usize)
+            .sum()
+    }
+
+    /**
+     * Number of indices in self but not in other,
+     * ie how many champions would have to be swapped out
+     * to turn this team into the other (if they're the same size)
+     */
+    pub fn num_different(&self, other: &TeamMask) -> usize {
+        self.len() - self.num_shared(other)
+    }
+
+    /**
+     * |A ∩ B| / |A ∪ B|, or 1 if both are empty
+     */
+    pub fn jaccard(&self, other: &TeamMask) -> f64 {
+        let shared = self.num_shared(other);
+        let total = self.len() + other.len() - shared;
+
+        if total == 0 {
+            1.0
+        } else {
+            (shared as f64) / (total as f64)
+        }
+    }
+
+    /**
+     * Indices in ascending order
+     */
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words
+            .iter()
+            .enumerate()
+            .flat_map(|(i, word)| {
+                let mut word = *word;
+
+                std::iter::from_fn(move || {
+                    if word == 0 {
+                        return None;
+                    }
+
+                    let bit = word.trailing_zeros() as usize;
+                    word &= word - 1;
+                    Some(i * 64 + bit)
+                })
+            })
+    }
+
+    /**
+     * Little endian 32 bit words, padded (or truncated) to num_words,
+     * eg to hand over to JS as part of a Uint32Array
+     */
+    pub fn to_u32_words(&self, num_words: usize) -> Vec<u32> {
+        let mut result = Vec::from_iter(
+            self.words
+                .iter()
+                .flat_map(|word| [*word as u32, (word >> 32) as u32])
+        );
+        result.resize(num_words, 0);
+
+        result
+    }
+
+    /**
+     * Words needed for a mask of every index below num_indices
+     */
+    pub fn num_u32_words(num_indices: usize) -> usize {
+        num_indices.div_ceil(32)
+    }
+
+    fn trimmed(mut words: Vec<u64>) -> Self {
+        while words.last() == Some(&0) {
+            words.pop();
+        }
+
+        Self { words }
+    }
+}
+
+impl FromIterator<usize> for TeamMask {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut mask = Self::new();
+        for idx in iter {
+            mask.insert(idx);
+        }
+
+        mask
+    }
+}
+
+impl<'a> FromIterator<&'a usize> for TeamMask {
+    fn from_iter<I: IntoIterator<Item = &'a usize>>(iter: I) -> Self {
+        iter.into_iter().copied().collect()
+    }
+}
+
+impl fmt::Debug for TeamMask {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TeamMask;
+
+    #[test]
+    fn test_set_operations() {
+        let a = TeamMask::from_iter([1, 3, 70, 200]);
+        let b = TeamMask::from_iter([3, 4, 200]);
+
+        assert_eq!(a.len(), 4);
+        assert!(a.contains(70));
+        assert!(!a.contains(71));
+        assert!(!a.contains(1000));
+
+        assert_eq!(Vec::from_iter(a.iter()), vec![1, 3, 70, 200]);
+        assert_eq!(a.union(&b), TeamMask::from_iter([1, 3, 4, 70, 200]));
+        assert_eq!(a.intersection(&b), TeamMask::from_iter([3, 200]));
+        assert_eq!(b.difference(&a), TeamMask::from_iter([4]));
+
+        assert_eq!(a.num_shared(&b), 2);
+        assert_eq!(a.num_different(&b), 2);
+        assert_eq!(a.jaccard(&b), 2.0 / 5.0);
+        assert_eq!(TeamMask::new().jaccard(&TeamMask::new()), 1.0);
+    }
+
+    #[test]
+    fn test_trimmed() {
+        // Same set, but the first still has a word for index 100
+        let a = TeamMask::from_iter([1, 100])
+            .difference(&TeamMask::from_iter([100]));
+        let b = TeamMask::from_iter([1]);
+
+        assert_eq!(a, b);
+        assert!(a.intersection(&TeamMask::from_iter([100])).is_empty());
+    }
+
+    #[test]
+    fn test_u32_words() {
+        let mask = TeamMask::from_iter([0, 33, 64]);
+        assert_eq!(TeamMask::num_u32_words(65), 3);
+        assert_eq!(mask.to_u32_words(4), vec![1, 2, 1, 0]);
+    }
+}
@@ -0,0 +1,196 @@
+use std::fmt::Write;
+
+use serde::Serialize;
+
+use crate::set_data::SetData;
+use crate::trait_hypergraph::TraitHypergraph;
+
+/**
+ * The pairwise trait graph of a set, for looking at why champions are (or aren't) connected
+ *
+ * Unlike the graphs the solvers work on, nothing is pruned,
+ * so every champion of the set data is included
+ */
+#[derive(Debug, PartialEq, Serialize)]
+pub struct TraitGraph {
+    // Same order as the set data
+    pub champions: Vec<TraitGraphChampion>,
+    // Sorted by source, then target
+    pub edges: Vec<TraitGraphEdge>,
+    pub num_components: usize,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct TraitGraphChampion {
+    // eg TFT11_Ahri
+    pub id: String,
+    pub name: String,
+    // Numbered by the first champion of each component
+    pub component: usize,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct TraitGraphEdge {
+    // Champion ids
+    pub source: String,
+    pub target: String,
+    // Ids of every trait the champions share
+    pub traits: Vec<String>,
+}
+
+impl TraitGraph {
+    pub fn new(set_data: &SetData) -> Self {
+        Self::from_hypergraph(
+            &TraitHypergraph::from_set_data(set_data),
+            set_data.champions()
+                .iter()
+                .map(|c| (c.id.clone(), c.display_name.clone()))
+        )
+    }
+
+    /**
+     * Champions are (id, name) pairs, in the order of the hypergraph
+     */
+    pub fn from_hypergraph(
+        traits: &TraitHypergraph,
+        champions: impl IntoIterator<Item = (String, String)>
+    ) -> Self {
+        let components = traits.components();
+        let num_components = components
+            .iter()
+            .max()
+            .map_or(0, |max| max + 1);
+
+        let champions = Vec::from_iter(
+            champions
+                .into_iter()
+                .zip(components)
+                .map(|((id, name), component)| TraitGraphChampion {
+                    id,
+                    name,
+                    component,
+                })
+        );
+
+        let mut pairs = Vec::from_iter(traits.edges());
+        pairs.sort();
+
+        let edges = Vec::from_iter(
+            pairs
+                .into_iter()
+                .map(|(a, b)| (a as usize, b as usize))
+                .map(|(a, b)| TraitGraphEdge {
+                    source: champions[a].id.clone(),
+                    target: champions[b].id.clone(),
+                    traits: Vec::from_iter(
+                        traits
+                            .shared_traits(a, b)
+                            .into_iter()
+                            .map(|t| traits.traits()[t].id.clone())
+                    ),
+                })
+        );
+
+        Self {
+            champions,
+            edges,
+            num_components,
+        }
+    }
+
+    /**
+     * Graphviz source, with a cluster per component
+     *   cargo run -- --trait-graph=dot champions.json traits.json | dot -Tsvg > graph.svg
+     */
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("graph traits {\n");
+
+        for component in 0..self.num_components {
+            writeln!(dot, "    subgraph cluster_{} {{", component).unwrap();
+
+            for champion in self.champions
+                .iter()
+                .filter(|c| c.component == component) {
+                writeln!(
+                    dot,
+                    "        {} [label={}];",
+                    quote(&champion.id),
+                    quote(&champion.name)
+                ).unwrap();
+            }
+
+            dot.push_str("    }\n");
+        }
+
+        for edge in self.edges.iter() {
+            writeln!(
+                dot,
+                "    {} -- {} [label={}];",
+                quote(&edge.source),
+                quote(&edge.target),
+                quote(&edge.traits.join(", "))
+            ).unwrap();
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::trait_hypergraph::TraitHypergraph;
+
+    use super::TraitGraph;
+
+    fn example() -> TraitGraph {
+        // A and B share two traits, C has none in common
+        let traits = TraitHypergraph::new(
+            3,
+            [
+                ("Fated".to_string(), vec![0, 1]),
+                ("Mythic".to_string(), vec![0, 1]),
+                ("Unique".to_string(), vec![2]),
+            ]
+        );
+
+        TraitGraph::from_hypergraph(
+            &traits,
+            [
+                ("TFT_A".to_string(), "A".to_string()),
+                ("TFT_B".to_string(), "B \"2\"".to_string()),
+                ("TFT_C".to_string(), "C".to_string()),
+            ]
+        )
+    }
+
+    #[test]
+    fn test_graph() {
+        let graph = example();
+
+        assert_eq!(graph.num_components, 2);
+        assert_eq!(
+            Vec::from_iter(graph.champions.iter().map(|c| c.component)),
+            vec![0, 0, 1]
+        );
+
+        assert_eq!(graph.edges.len(), 1);
+        assert_eq!(graph.edges[0].source, "TFT_A");
+        assert_eq!(graph.edges[0].target, "TFT_B");
+        assert_eq!(graph.edges[0].traits, vec!["Fated", "Mythic"]);
+    }
+
+    #[test]
+    fn test_dot() {
+        let dot = example().to_dot();
+
+        assert!(dot.starts_with("graph traits {\n"));
+        assert!(dot.contains("    subgraph cluster_1 {\n        \"TFT_C\" [label=\"C\"];\n    }"));
+        assert!(dot.contains("\"TFT_B\" [label=\"B \\\"2\\\"\"];"));
+        assert!(dot.contains("\"TFT_A\" -- \"TFT_B\" [label=\"Fated, Mythic\"];"));
+    }
+}
@@ -0,0 +1,236 @@
+use std::collections::HashSet;
+
+use serde::Serialize;
+
+use crate::set_data::SetData;
+
+/**
+ * Traits as hyperedges over the champions that have them
+ *
+ * The trait graph the solvers work on (champions are adjacent if they share any trait)
+ * is the pairwise projection of this, see edges().
+ * Keeping the hyperedges means we can still tell which traits connect two champions,
+ * or how many champions of each trait a team has.
+ */
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TraitHypergraph {
+    num_champions: usize,
+    // Sorted by id
+    traits: Vec<TraitEdge>,
+    // Indices into traits for each champion, ascending
+    traits_of: Vec<Vec<usize>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct TraitEdge {
+    // eg TFT11_Fated
+    pub id: String,
+    // Ascending
+    pub champions: Vec<usize>,
+}
+
+impl TraitHypergraph {
+    /**
+     * Traits without any champions are left out
+     */
+    pub fn new(
+        num_champions: usize,
+        traits: impl IntoIterator<Item = (String, Vec<usize>)>
+    ) -> Self {
+        let mut traits = Vec::from_iter(
+            traits
+                .into_iter()
+                .map(|(id, mut champions)| {
+                    champions.sort();
+                    champions.dedup();
+
+                    TraitEdge { id, champions }
+                })
+                .filter(|t| !t.champions.is_empty())
+        );
+        traits.sort_by(|a, b| a.id.cmp(&b.id));
+
+        let mut traits_of = vec![vec![]; num_champions];
+        for (idx, t) in traits.iter().enumerate() {
+            for champion in t.champions.iter() {
+                traits_of[*champion].push(idx);
+            }
+        }
+
+        Self {
+            num_champions,
+            traits,
+            traits_of,
+        }
+    }
+
+    pub fn from_set_data(set_data: &SetData) -> Self {
+        Self::new(set_data.champions().len(), set_data.champions_by_trait())
+    }
+
+    pub fn traits(&self) -> &[TraitEdge] {
+        &self.traits
+    }
+
+    /**
+     * Indices into traits(), ascending
+     */
+    pub fn traits_of(&self, champion: usize) -> &[usize] {
+        &self.traits_of[champion]
+    }
+
+    /**
+     * Indices into traits() of every trait both champions have, ascending
+     */
+    pub fn shared_traits(&self, a: usize, b: usize) -> Vec<usize> {
+        let of_b = self.traits_of(b);
+
+        Vec::from_iter(
+            self.traits_of(a)
+                .iter()
+                .filter(|t| of_b.binary_search(t).is_ok())
+                .copied()
+        )
+    }
+
+    /**
+     * Pairwise projection, with each pair of champions that share a trait once (lower index first)
+     */
+    pub fn edges(&self) -> HashSet<(i32, i32)> {
+        HashSet::from_iter(
+            self.traits.iter().flat_map(|t| {
+                t.champions
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(i, a)|
+                        t.champions[i + 1..]
+                            .iter()
+                            .map(move |b| (*a as i32, *b as i32))
+                    )
+            })
+        )
+    }
+
+    /**
+     * Connected component of each champion, numbered by their lowest champion
+     * (champions without shared traits are components of their own)
+     */
+    pub fn components(&self) -> Vec<usize> {
+        let mut component = vec![usize::MAX; self.num_champions];
+        let mut num_components = 0;
+
+        for start in 0..self.num_champions {
+            if component[start] != usize::MAX {
+                continue;
+            }
+
+            component[start] = num_components;
+            let mut stack = vec![start];
+            while let Some(champion) = stack.pop() {
+                for t in self.traits_of(champion) {
+                    for other in self.traits[*t].champions.iter() {
+                        if component[*other] == usize::MAX {
+                            component[*other] = num_components;
+                            stack.push(*other);
+                        }
+                    }
+                }
+            }
+
+            num_components += 1;
+        }
+
+        component
+    }
+
+    /**
+     * Traits the team has, with the members of the team that have them
+     */
+    pub fn team_traits(&self, team: &[usize]) -> Vec<(&TraitEdge, Vec<usize>)> {
+        let mut team = team.to_vec();
+        team.sort();
+
+        Vec::from_iter(
+            self.traits
+                .iter()
+                .map(|t| (
+                    t,
+                    Vec::from_iter(
+                        t.champions
+                            .iter()
+                            .filter(|c| team.binary_search(c).is_ok())
+                            .copied()
+                    ),
+                ))
+                .filter(|(_, members)| !members.is_empty())
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::TraitHypergraph;
+
+    /**
+     * A: 0, 1, 2
+     * B: 2, 3
+     * C: 1, 2
+     * D: 4
+     */
+    fn example() -> TraitHypergraph {
+        TraitHypergraph::new(
+            5,
+            [
+                ("B".to_string(), vec![3, 2]),
+                ("A".to_string(), vec![0, 1, 2, 1]),
+                ("C".to_string(), vec![1, 2]),
+                ("D".to_string(), vec![4]),
+                ("E".to_string(), vec![]),
+            ]
+        )
+    }
+
+    #[test]
+    fn test_traits() {
+        let graph = example();
+
+        let ids = Vec::from_iter(graph.traits().iter().map(|t| t.id.as_str()));
+        assert_eq!(ids, vec!["A", "B", "C", "D"]);
+        assert_eq!(graph.traits()[0].champions, vec![0, 1, 2]);
+
+        assert_eq!(graph.traits_of(2), &[0, 1, 2]);
+        assert_eq!(graph.shared_traits(1, 2), vec![0, 2]);
+        assert!(graph.shared_traits(0, 3).is_empty());
+    }
+
+    #[test]
+    fn test_edges() {
+        assert_eq!(
+            example().edges(),
+            HashSet::from_iter([(0, 1), (0, 2), (1, 2), (2, 3)])
+        );
+    }
+
+    #[test]
+    fn test_components() {
+        assert_eq!(example().components(), vec![0, 0, 0, 0, 1]);
+    }
+
+    #[test]
+    fn test_team_traits() {
+        let graph = example();
+        let traits = Vec::from_iter(
+            graph
+                .team_traits(&[3, 1, 2])
+                .into_iter()
+                .map(|(t, members)| (t.id.as_str(), members))
+        );
+
+        assert_eq!(
+            traits,
+            vec![("A", vec![1, 2]), ("B", vec![2, 3]), ("C", vec![1, 2])]
+        );
+    }
+}
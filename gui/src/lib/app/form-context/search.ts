@@ -1,10 +1,35 @@
 import type { FilterForm } from '$lib/app/form-context/types'
 import { applyAttributeFilterWithGlobal } from '$lib/app/form-context/utils'
-import { CHAMPIONS_BY_ID } from '$lib/constants'
-import { invert, range } from 'radash'
+import ALL_CHAMPIONS from '$lib/assets/tft/merged_teamplanner_data.json'
+import ALL_TRAITS from '$lib/assets/tft/traits.json'
+import { range } from 'radash'
 
 const worker = new Worker('worker.js')
 
+// Requests waiting for the worker's reply, by request id
+const pending = new Map<
+    number,
+    { resolve: (value: any) => void; reject: (error: Error) => void }
+>()
+let nextRequestId = 0
+
+worker.addEventListener('message', (ev) => {
+    const { id, type } = ev.data ?? {}
+
+    const request = pending.get(id)
+    if (!request) {
+        return
+    }
+    pending.delete(id)
+
+    if (type === 'error') {
+        const { code, message } = ev.data
+        request.reject(Object.assign(new Error(message), { code }))
+    } else {
+        request.resolve(ev.data.value)
+    }
+})
+
 // Worker's init function is async
 const workerInit = new Promise((resolve) =>
     worker.addEventListener('message', (ev) => {
@@ -12,6 +37,13 @@ const workerInit = new Promise((resolve) =>
             resolve(true)
         }
     })
+).then(() =>
+    // Slots and results refer to champions by their character_id
+    request({
+        type: 'setData',
+        champions: JSON.stringify(ALL_CHAMPIONS),
+        traits: JSON.stringify(ALL_TRAITS)
+    })
 )
 
 function getSlotOptions(form: FilterForm): Array<string[]> {
@@ -45,59 +77,37 @@ function getSlotOptions(form: FilterForm): Array<string[]> {
     return slots
 }
 
-export async function setSearchOptions(form: FilterForm) {
-    const slotOptions = getSlotOptions(form)
-
-    const allChampions: Set<string> = new Set()
-    for (let opts of slotOptions) {
-        for (let c of opts) {
-            allChampions.add(c)
-        }
-    }
-
-    const champion_to_var = [...allChampions.values()].reduce(
-        (acc, id, idx) => {
-            // Filtering champs globally banned reduces calculation time
-            // @todo: this should really be done on rust side
-            if (allChampions.has(id)) {
-                acc[id] = idx
-            }
-
-            return acc
-        },
-        {} as Record<string, number>
-    )
-
-    const var_to_champion = invert(champion_to_var)
-
-    const slots = slotOptions.map((cs) =>
-        cs.map((c) => champion_to_var[c])
-    )
-
-    const traits = new Map()
-    ;[...allChampions.values()]
-        .map((id) => CHAMPIONS_BY_ID[id])
-        .map((c) => [
-            champion_to_var[c.character_id],
-            c.traits.map((t) => t.name)
-        ])
-        .forEach(([k, v]) => traits.set(k as number, v as string[]))
-
-    const num_champions = Object.values(champion_to_var).length
+export async function setSearchOptions(
+    form: FilterForm,
+    cursor?: wasm_bindgen.ISearchCursor
+) {
+    const slots = getSlotOptions(form)
 
     const options = {
-        num_champions,
         team_size: form.teamSize,
-        slots,
-        traits
+        slots
     }
 
     await workerInit
 
-    worker.postMessage({
+    await request({
         type: 'setOptions',
         options,
-        var_to_champion
+        cursor
+    })
+}
+
+// Can be passed back into setSearchOptions() to skip the teams that were already returned
+export async function getSearchCursor(): Promise<wasm_bindgen.ISearchCursor> {
+    return request({
+        type: 'getCursor'
+    })
+}
+
+// Only updates between batches of results, since the worker is busy while fetching them
+export async function getSearchProgress(): Promise<wasm_bindgen.ISearchProgress> {
+    return request({
+        type: 'getProgress'
     })
 }
 
@@ -106,18 +116,25 @@ export async function getSearchResult(
 ): Promise<string[][]> {
     const start = Date.now()
 
-    worker.postMessage({
+    const results = await request<string[][]>({
         type: 'nextSolution',
         batchSize
     })
+    console.log(
+        `Fetched ${batchSize} results in ${Math.floor(Date.now() - start)}ms`
+    )
 
-    return new Promise((resolve) => {
-        worker.onmessage = (ev) => {
-            console.log(
-                `Fetched ${batchSize} results in ${Math.floor(Date.now() - start)}ms`
-            )
+    return results
+}
 
-            resolve(ev.data)
-        }
+// Every message gets a reply with the same id, so requests can overlap
+// (eg polling progress while a batch of results is being fetched).
+// Rejects with a wasm_bindgen.ISearchError if the worker reports an error
+function request<T>(message: object): Promise<T> {
+    const id = nextRequestId++
+
+    return new Promise((resolve, reject) => {
+        pending.set(id, { resolve, reject })
+        worker.postMessage({ ...message, id })
     })
 }
@@ -5,9 +5,25 @@ declare namespace wasm_bindgen {
 	
 	export interface ISearchTeamsOptions {
 	    team_size: number
-	    num_champions: number
-	    slots: Array<number[]>
-	    traits: Map<number, string[]>
+	    // Champion ids, eg TFT11_Ahri
+	    // With fewer slots than team_size the remaining members can be any champion
+	    slots: Array<string[]>
+	
+	    diversity?: number
+	
+	    // Searches that only need connectivity skip the SAT solver,
+	    // unless a backend is set explicitly
+	    backend?: 'minisat' | 'cdcl'
+	
+	    // For debugging, eg without 'connectivity' any champions matching the slots form a team
+	    disabled_constraints?: IConstraintFamily[]
+	
+	    // Estimated clauses above which a cheaper encoding is used,
+	    // or SEARCH_TOO_LARGE is thrown if that's still too large
+	    max_clauses?: number
+	
+	    // Minimum number of other team members each champion shares a trait with
+	    min_synergy?: number
 	
 	    debug?: boolean
 	}
@@ -15,12 +31,124 @@ declare namespace wasm_bindgen {
 	
 	
 	
+	export interface IDecodedOptions {
+	    options: ISearchTeamsOptions
+	    // Slot options that aren't in the loaded set data, eg after a patch,
+	    // which were left out of the slots
+	    unknown_champions: string[]
+	}
+	
+	export interface ISimilarTeam {
+	    champion_ids: string[]
+	    // Jaccard similarity, from 0 to 1
+	    similarity: number
+	}
+	
+	export interface ITraitGraph {
+	    champions: Array<{
+	        id: string
+	        name: string
+	        component: number
+	    }>
+	    // Champion ids, with the ids of every trait they share
+	    edges: Array<{
+	        source: string
+	        target: string
+	        traits: string[]
+	    }>
+	    num_components: number
+	}
+	
+	export interface IBackbone {
+	    // Champion ids in every team matching the options
+	    forced_in: string[]
+	    // Champion ids in none of them
+	    forced_out: string[]
+	}
+	
+	export interface IPrunedChampion {
+	    id: string
+	    reason: 'outside_slots' | 'isolated' | 'component_too_small'
+	}
+	
+	export type IConstraintFamily =
+	    | 'decisions'
+	    | 'connectivity'
+	    | 'no_repeat'
+	    | 'edges'
+	    | 'slot_coverage'
+	    | 'slot_dedupe'
+	    | 'min_degree'
+	    | 'custom'
+	    | 'imported'
+	
+	export interface IConstraintStats {
+	    variables: number
+	    clauses: number
+	    literals: number
+	    families: Array<{
+	        family: IConstraintFamily
+	        constraints: number
+	        clauses: number
+	        literals: number
+	    }>
+	}
+	
+	export interface ISearchProgress {
+	    teams_found: number
+	    // Only counted by the cdcl backend
+	    conflicts?: number
+	    elapsed_ms: number
+	    // Estimate from 0 to 1
+	    fraction_searched: number
+	}
+	
+	
+	
+	export type ISearchErrorCode =
+	    | 'MALFORMED_OPTIONS'
+	    | 'MALFORMED_CURSOR'
+	    | 'CURSOR_MISMATCH'
+	    | 'UNKNOWN_CHAMPION'
+	    | 'TEAM_TOO_LARGE'
+	    | 'TOO_MANY_SLOTS'
+	    | 'EMPTY_SLOT'
+	    | 'UNKNOWN_BACKEND'
+	    | 'INVALID_PARTITION'
+	    | 'SEARCH_TOO_LARGE'
+	    | 'UNKNOWN_TRAIT'
+	    | 'INVALID_SET_DATA'
+	    | 'INTERNAL'
+	
+	export interface ISearchError extends Error {
+	    code: ISearchErrorCode
+	}
+	
+	
+	
+	export interface ISearchCursor {
+	    options_hash: string
+	    teams: Array<string[]>
+	}
+	
+	
+	
 	interface Team {
-	    champion_ids: number[]
+	    champion_ids: string[]
+	}
+	
+	export interface ITeamTrait {
+	    id: string
+	    champion_ids: string[]
 	}
 	
 	
 	
+	/**
+	* @param {any} options
+	* @returns {string}
+	*/
+	export function encode_options(options: any): string;
 	/**
 	*/
 	export class TeamFinder {
@@ -30,9 +158,114 @@ declare namespace wasm_bindgen {
 	*/
 	  static new(): TeamFinder;
 	/**
+	* Contents of merged_teamplanner_data.json and traits.json
+	* Must be called before reset(), slots refer to champions by their id in this data
+	*
+	* Ends the current search, since its teams refer to the previous champions
+	* @param {string} champions_json
+	* @param {string} traits_json
+	*/
+	  load_set_data(champions_json: string, traits_json: string): void;
+	/**
+	* Options from encode_options(), decoded against the loaded set data
+	* so champions that are no longer in it are dropped from the slots
+	* (IDecodedOptions)
+	* @param {string} encoded
+	* @returns {any}
+	*/
+	  decode_options(encoded: string): any;
+	/**
+	* If a cursor from a previous search with the same options is passed in,
+	* the teams it already returned are skipped
+	*
+	* Returns the size of the encoding (IConstraintStats)
+	* @param {any} options
+	* @param {any} cursor
+	* @returns {any}
+	*/
+	  reset(options: any, cursor: any): any;
+	/**
+	* Like reset(), but only searches slice `index` of `count` disjoint slices of the teams,
+	* so the search can be split across web workers that each call this with the same options
+	*
+	* The cursor can be the merged cursors of every partition,
+	* teams in other slices are skipped anyway
 	* @param {any} options
+	* @param {any} cursor
+	* @param {number} index
+	* @param {number} count
+	* @returns {any}
+	*/
+	  reset_partition(options: any, cursor: any, index: number, count: number): any;
+	/**
+	* @returns {any}
+	*/
+	  cursor(): any;
+	/**
+	* Teams returned so far as bitsets over the champions of the set data,
+	* TeamFinder.mask_words() words per team
+	* (champion i is bit i % 32 of word i / 32)
+	* @returns {Uint32Array}
+	*/
+	  team_masks(): Uint32Array;
+	/**
+	* @returns {number}
+	*/
+	  mask_words(): number;
+	/**
+	* Up to `count` of the teams returned so far,
+	* most similar to these champions first (ISimilarTeam[])
+	* @param {(string)[]} champion_ids
+	* @param {number} count
+	* @returns {any}
+	*/
+	  similar(champion_ids: (string)[], count: number): any;
+	/**
+	* Traits of a team and which of its champions have them (ITeamTrait[]),
+	* eg to show why the champions of a team are connected
+	* @param {(string)[]} champion_ids
+	* @returns {any}
+	*/
+	  team_traits(champion_ids: (string)[]): any;
+	/**
+	* Every champion of the set data and the traits they share (ITraitGraph),
+	* regardless of the current search
+	* @returns {any}
+	*/
+	  trait_graph(): any;
+	/**
+	* Number of connected components of the trait graph being searched,
+	* each has its own solver
+	* @returns {number}
+	*/
+	  num_components(): number;
+	/**
+	* CNF of one component of the current search in DIMACS format, for reproducing slow searches in other SAT tools
+	* (excludes the clauses blocking teams that were already returned)
+	*
+	* Undefined if the component isn't searched with a SAT solver
+	* @param {number} component
+	* @returns {string | undefined}
+	*/
+	  dimacs(component: number): string | undefined;
+	/**
+	* Champions that were left out of the current search
+	* because they can't be in any team matching the options
+	* @returns {any}
+	*/
+	  pruned(): any;
+	/**
+	* Champions that are in every team matching the options, or in none (IBackbone),
+	* eg to grey out champions the current filters rule out
+	* (teams that were already returned still count)
+	* @returns {any}
+	*/
+	  backbone(): any;
+	/**
+	* Can be polled between calls to next() to show how far along the search is
+	* @returns {any}
 	*/
-	  reset(options: any): void;
+	  progress(): any;
 	/**
 	* @returns {any}
 	*/
@@ -45,9 +278,24 @@ declare type InitInput = RequestInfo | URL | Response | BufferSource | WebAssemb
 
 declare interface InitOutput {
   readonly memory: WebAssembly.Memory;
+  readonly encode_options: (a: number, b: number) => void;
   readonly __wbg_teamfinder_free: (a: number) => void;
   readonly teamfinder_new: () => number;
-  readonly teamfinder_reset: (a: number, b: number) => void;
+  readonly teamfinder_load_set_data: (a: number, b: number, c: number, d: number, e: number) => void;
+  readonly teamfinder_decode_options: (a: number, b: number, c: number, d: number) => void;
+  readonly teamfinder_pruned: (a: number, b: number) => void;
+  readonly teamfinder_reset: (a: number, b: number, c: number, d: number) => void;
+  readonly teamfinder_reset_partition: (a: number, b: number, c: number, d: number, e: number, f: number, g: number) => void;
+  readonly teamfinder_cursor: (a: number) => number;
+  readonly teamfinder_team_masks: (a: number) => number;
+  readonly teamfinder_mask_words: (a: number) => number;
+  readonly teamfinder_similar: (a: number, b: number, c: number, d: number, e: number) => void;
+  readonly teamfinder_team_traits: (a: number, b: number, c: number, d: number) => void;
+  readonly teamfinder_trait_graph: (a: number, b: number) => void;
+  readonly teamfinder_num_components: (a: number) => number;
+  readonly teamfinder_dimacs: (a: number, b: number, c: number) => void;
+  readonly teamfinder_backbone: (a: number, b: number) => void;
+  readonly teamfinder_progress: (a: number, b: number) => void;
   readonly teamfinder_next: (a: number) => number;
   readonly __wbindgen_malloc: (a: number, b: number) => number;
   readonly __wbindgen_realloc: (a: number, b: number, c: number, d: number) => number;
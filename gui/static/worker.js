@@ -10,40 +10,73 @@ async function init_wasm_in_worker() {
 
     const finder = TeamFinder.new()
 
-    let ctx = {}
-
     // Set callback to handle messages passed to the worker.
+    // Every message is answered with its own result or error,
+    // tagged with the id it was sent with
     self.onmessage = async (event) => {
         console.log('Got worker event', event.data)
+        const { id } = event.data
+
+        try {
+            const value = handleMessage(event)
+            self.postMessage({ id, type: 'result', value })
+        } catch (e) {
+            // Errors thrown by the finder have a code (see ISearchErrorCode)
+            console.error(e)
+            self.postMessage({
+                id,
+                type: 'error',
+                code: e.code ?? 'INTERNAL',
+                message: e.message
+            })
+        }
+    }
 
+    function handleMessage(event) {
         switch (event.data.type) {
+            case 'setData':
+                const { champions, traits } = event.data
+
+                finder.load_set_data(champions, traits)
+
+                return null
             case 'setOptions':
-                const { options, var_to_champion } = event.data
+                const { options, cursor, partition } = event.data
 
-                finder.reset(options)
+                // Workers sharing a search each take one slice of it
+                const stats = partition
+                    ? finder.reset_partition(
+                          options,
+                          cursor,
+                          partition.index,
+                          partition.count
+                      )
+                    : finder.reset(options, cursor)
 
-                ctx = { var_to_champion }
+                console.log('Constraint stats', stats)
 
-                return
+                return stats
             case 'nextSolution':
                 const { batchSize } = event.data
 
                 const results = []
 
                 for (let idx = 0; idx < batchSize; idx++) {
-                    const teamVars = finder.next()
-                    if (!teamVars) {
+                    const teamIds = finder.next()
+                    if (!teamIds) {
                         break
                     }
 
-                    const teamIds = teamVars.map(
-                        (v) => ctx.var_to_champion[v]
-                    )
                     results.push(teamIds)
                 }
 
-                self.postMessage(results)
-                return
+                return results
+            case 'getCursor':
+                return finder.cursor()
+            case 'getProgress':
+                return finder.progress()
+            default:
+                throw new Error(`Unknown message type ${event.data.type}`)
         }
     }
 